@@ -6,17 +6,58 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
 const SERVER_URL: &str = "https://booleanclient.online";
-const LAUNCH_ZIP_URL: &str = "https://github.com/zxctehas1337/1/releases/download/Beta/launch.zip";
+/// Версия игры и загрузчика, которые ставит лаунчер
+const MINECRAFT_VERSION: &str = "1.21.4";
+const FABRIC_LOADER_VERSION: &str = "0.16.10";
 const FABRIC_API_URL: &str = "https://cdn.modrinth.com/data/P7dR8mSH/versions/KEv54FjE/fabric-api-0.111.0%2B1.21.4.jar";
 const FABRIC_API_VERSION: &str = "0.111.0+1.21.4";
 const SODIUM_URL: &str = "https://cdn.modrinth.com/data/AANobbMI/versions/c3YkZvne/sodium-fabric-0.6.13%2Bmc1.21.4.jar";
 const SODIUM_VERSION: &str = "0.6.13+mc1.21.4";
+/// Адреса Mojang и Fabric meta по умолчанию
+const RESOURCES_URL: &str = "https://resources.download.minecraft.net";
+const LIBRARIES_URL: &str = "https://libraries.minecraft.net/";
+const JAVA_RUNTIME_MANIFEST_URL: &str = "https://launchermeta.mojang.com/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json";
+const FABRIC_META_URL: &str = "https://meta.fabricmc.net";
 
 mod download;
 mod version;
 mod mods;
 mod launch;
 mod java;
+mod vanilla;
+mod fabric;
+mod catalog;
+mod libraries;
+
+pub use catalog::CatalogFilter;
+pub(crate) use download::sha1_hex;
+pub(crate) use java::java_major_version;
+
+use crate::minecraft::manifest::{ManifestClient, VERSION_MANIFEST_URL};
+
+/// Адреса Mojang и Fabric meta, через которые идут все запросы установщика и лаунчера.
+/// Тесты подставляют сюда адрес локального сервера.
+#[derive(Clone, Debug)]
+pub struct Endpoints {
+    pub version_manifest_url: String,
+    pub resources_url: String,
+    /// Maven-репозиторий для библиотек без `url` и без `downloads`
+    pub libraries_url: String,
+    pub java_runtime_manifest_url: String,
+    pub fabric_meta_url: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            version_manifest_url: VERSION_MANIFEST_URL.to_string(),
+            resources_url: RESOURCES_URL.to_string(),
+            libraries_url: LIBRARIES_URL.to_string(),
+            java_runtime_manifest_url: JAVA_RUNTIME_MANIFEST_URL.to_string(),
+            fabric_meta_url: FABRIC_META_URL.to_string(),
+        }
+    }
+}
 
 #[derive(Serialize, Clone)]
 pub struct InstallProgress {
@@ -51,12 +92,23 @@ pub struct ClientInstaller {
     mods_dir: PathBuf,
     /// Скрытая директория для JAR чита
     hidden_client_dir: PathBuf,
+    /// Адреса Mojang и Fabric meta
+    endpoints: Endpoints,
     client: Client,
 }
 
 impl ClientInstaller {
     /// Создаёт инсталлер с путём из настроек пользователя
     pub fn new_with_path(base_dir: PathBuf, user_install_path: Option<String>) -> Self {
+        Self::with_endpoints(base_dir, user_install_path, Endpoints::default())
+    }
+
+    /// Создаёт инсталлер, который ходит по заданным адресам вместо Mojang и Fabric meta
+    pub fn with_endpoints(
+        base_dir: PathBuf,
+        user_install_path: Option<String>,
+        endpoints: Endpoints,
+    ) -> Self {
         // Определяем путь для Minecraft файлов
         let user_dir = match user_install_path {
             Some(path) if !path.is_empty() => PathBuf::from(path),
@@ -75,6 +127,7 @@ impl ClientInstaller {
             launch_dir,
             mods_dir,
             hidden_client_dir,
+            endpoints,
             client: Client::new(),
         }
     }
@@ -110,6 +163,15 @@ impl ClientInstaller {
         }
     }
 
+    /// Клиент манифеста версий с адресом из `endpoints`
+    pub(crate) fn manifest_client(&self) -> ManifestClient {
+        ManifestClient::new(
+            self.client.clone(),
+            &self.launch_dir,
+            &self.endpoints.version_manifest_url,
+        )
+    }
+
     pub fn get_mods_dir(&self) -> &Path {
         &self.mods_dir
     }
@@ -0,0 +1,88 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::fabric::FabricLoaderVersion;
+use super::ClientInstaller;
+use crate::minecraft::model::LatestVersions;
+
+/// Версия игры, доступная для установки
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailableVersion {
+    pub id: String,
+    /// release, snapshot, old_beta или old_alpha
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub release_time: Option<String>,
+    /// Загрузчики, которые можно поставить на эту версию
+    pub loaders: Vec<String>,
+}
+
+/// Каталог версий для выбора во фронтенде
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionCatalog {
+    pub latest: LatestVersions,
+    pub versions: Vec<AvailableVersion>,
+    /// Версии Fabric Loader, подходящие к любой версии с `fabric` в `loaders`
+    pub fabric_loaders: Vec<FabricLoaderVersion>,
+}
+
+/// Фильтр каталога. Пустые списки означают «без ограничений».
+#[derive(Debug, Default, Clone)]
+pub struct CatalogFilter {
+    pub types: Vec<String>,
+    pub loader: Option<String>,
+}
+
+impl ClientInstaller {
+    /// Собирает каталог из манифеста Mojang и Fabric meta.
+    /// Без Fabric meta возвращаются только vanilla версии.
+    pub async fn available_versions(&self, filter: &CatalogFilter) -> Result<VersionCatalog> {
+        let manifest = self.manifest_client().version_manifest().await?;
+
+        let (fabric_games, fabric_loaders) = match tokio::try_join!(
+            self.fabric_game_versions(),
+            self.fabric_all_loader_versions()
+        ) {
+            Ok((games, loaders)) => (
+                games.into_iter().map(|g| g.version).collect::<HashSet<_>>(),
+                loaders,
+            ),
+            Err(e) => {
+                log::warn!("Fabric meta is unavailable: {}", e);
+                (HashSet::new(), Vec::new())
+            }
+        };
+
+        let versions = manifest
+            .versions
+            .into_iter()
+            .filter(|v| filter.types.is_empty() || filter.types.contains(&v.kind))
+            .map(|v| {
+                let mut loaders = Vec::new();
+                if fabric_games.contains(&v.id) {
+                    loaders.push("fabric".to_string());
+                }
+                AvailableVersion {
+                    id: v.id,
+                    kind: v.kind,
+                    release_time: v.release_time,
+                    loaders,
+                }
+            })
+            .filter(|v| match &filter.loader {
+                Some(loader) => v.loaders.contains(loader),
+                None => true,
+            })
+            .collect();
+
+        Ok(VersionCatalog {
+            latest: manifest.latest,
+            versions,
+            fabric_loaders,
+        })
+    }
+}
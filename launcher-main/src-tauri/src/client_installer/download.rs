@@ -1,14 +1,200 @@
 use std::fs;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use anyhow::Result;
-use futures_util::StreamExt;
+use futures_util::{stream, StreamExt};
+use sha1::{Digest, Sha1};
 use tauri::{AppHandle, Emitter, Runtime};
 
 use super::{ClientInstaller, InstallProgress};
+use crate::minecraft::model::Download;
+
+/// Сколько файлов качаем параллельно
+const PARALLEL_DOWNLOADS: usize = 16;
+/// Сколько раз пробуем скачать файл, прежде чем сдаться
+const DOWNLOAD_ATTEMPTS: u32 = 3;
+
+/// Файл для скачивания: откуда, куда и чем проверить
+pub(super) struct DownloadTask {
+    pub(super) url: String,
+    pub(super) dest: PathBuf,
+    pub(super) sha1: Option<String>,
+    pub(super) size: Option<u64>,
+}
+
+impl DownloadTask {
+    pub(super) fn from_download(download: &Download, dest: &Path) -> Self {
+        DownloadTask {
+            url: download.url.clone(),
+            dest: dest.to_path_buf(),
+            sha1: download.sha1.clone(),
+            size: download.size,
+        }
+    }
+}
+
+/// SHA-1 данных в hex
+pub(crate) fn sha1_hex(data: &[u8]) -> String {
+    hex::encode(Sha1::digest(data))
+}
+
+/// SHA-1 файла в hex
+pub(crate) fn file_sha1(path: &Path) -> std::io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha1::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Файл уже на диске и нужного размера (без хеша; размер неизвестен — просто не пустой)
+pub(crate) fn is_file_present(path: &Path, size: Option<u64>) -> bool {
+    match fs::metadata(path) {
+        Ok(m) if m.is_file() => match size {
+            Some(size) => m.len() == size,
+            None => m.len() > 0,
+        },
+        _ => false,
+    }
+}
+
+/// Проверяет, что файл на диске совпадает с ожидаемым размером и хешем
+pub(crate) fn is_file_valid(path: &Path, sha1: Option<&str>, size: Option<u64>) -> bool {
+    let metadata = match fs::metadata(path) {
+        Ok(m) if m.is_file() => m,
+        _ => return false,
+    };
+
+    if let Some(size) = size {
+        if metadata.len() != size {
+            return false;
+        }
+    }
+
+    match sha1 {
+        Some(expected) => file_sha1(path)
+            .map(|actual| actual.eq_ignore_ascii_case(expected))
+            .unwrap_or(false),
+        None => metadata.len() > 0,
+    }
+}
 
 impl ClientInstaller {
+    /// Скачивает файл с проверкой размера и SHA-1. Уже валидный файл не качается повторно.
+    /// Сетевые ошибки и несовпадение хеша повторяются до `DOWNLOAD_ATTEMPTS` раз.
+    pub(super) async fn download_verified(&self, task: &DownloadTask) -> Result<()> {
+        if is_file_valid(&task.dest, task.sha1.as_deref(), task.size) {
+            return Ok(());
+        }
+
+        let mut attempt = 1;
+        loop {
+            match self.download_once(task).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt >= DOWNLOAD_ATTEMPTS => return Err(e),
+                Err(e) => {
+                    log::warn!("Download of {} failed (attempt {}): {}", task.url, attempt, e);
+                    tokio::time::sleep(std::time::Duration::from_millis(500 * attempt as u64)).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    async fn download_once(&self, task: &DownloadTask) -> Result<()> {
+        if let Some(parent) = task.dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let response = self.client.get(&task.url).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow::anyhow!(
+                "Ошибка скачивания {}: статус {}",
+                task.url,
+                status
+            ));
+        }
+
+        // Пишем во временный файл, чтобы оборванная загрузка не выглядела готовой
+        let mut part_name = task.dest.file_name().unwrap_or_default().to_os_string();
+        part_name.push(".part");
+        let part_path = task.dest.with_file_name(part_name);
+        let mut file = fs::File::create(&part_path)?;
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            file.write_all(&chunk)?;
+        }
+        drop(file);
+
+        if !is_file_valid(&part_path, task.sha1.as_deref(), task.size) {
+            let _ = fs::remove_file(&part_path);
+            return Err(anyhow::anyhow!(
+                "Файл {} не прошёл проверку размера/SHA-1",
+                task.url
+            ));
+        }
+
+        fs::rename(&part_path, &task.dest)?;
+        Ok(())
+    }
+
+    /// Параллельно скачивает набор файлов, отправляя прогресс по мере завершения.
+    /// Качает все файлы, даже если какие-то не скачались, и возвращает первую ошибку.
+    pub(super) async fn download_all<R: Runtime>(
+        &self,
+        tasks: Vec<DownloadTask>,
+        app: &AppHandle<R>,
+        stage: &str,
+        message: &str,
+    ) -> Result<()> {
+        match self.download_each(tasks, app, stage, message).await.into_iter().next() {
+            Some((_, e)) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Параллельно скачивает все файлы и возвращает те, что скачать не удалось
+    pub(super) async fn download_each<R: Runtime>(
+        &self,
+        tasks: Vec<DownloadTask>,
+        app: &AppHandle<R>,
+        stage: &str,
+        message: &str,
+    ) -> Vec<(DownloadTask, anyhow::Error)> {
+        let total = tasks.len();
+        if total == 0 {
+            return Vec::new();
+        }
+
+        let done = AtomicUsize::new(0);
+        let done = &done;
+        let results: Vec<_> = stream::iter(tasks)
+            .map(|task| async move {
+                let result = self.download_verified(&task).await;
+                let finished = done.fetch_add(1, Ordering::Relaxed) + 1;
+                if finished == total || finished % 25 == 0 {
+                    let progress = (finished as f64 / total as f64) * 100.0;
+                    let _ = app.emit(
+                        "client-install-progress",
+                        InstallProgress {
+                            stage: stage.to_string(),
+                            progress,
+                            message: format!("{} ({}/{})", message, finished, total),
+                        },
+                    );
+                }
+                result.err().map(|e| (task, e))
+            })
+            .buffer_unordered(PARALLEL_DOWNLOADS)
+            .collect()
+            .await;
+
+        results.into_iter().flatten().collect()
+    }
+
     /// Скачивает файл. Если force=true, удаляет существующий файл и качает заново.
     pub(super) async fn download_file_force<R: Runtime>(
         &self,
@@ -0,0 +1,163 @@
+use std::fs;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+
+use super::{ClientInstaller, InstallProgress, FABRIC_LOADER_VERSION, MINECRAFT_VERSION};
+use crate::minecraft::model::{parse_json, VersionJson};
+
+/// Версия Fabric Loader из Fabric meta
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FabricLoaderVersion {
+    pub version: String,
+    #[serde(default)]
+    pub stable: bool,
+}
+
+/// Элемент ответа /v2/versions/loader/<game>
+#[derive(Deserialize)]
+struct GameLoaderEntry {
+    loader: FabricLoaderVersion,
+}
+
+/// Версия игры, которую поддерживает Fabric (/v2/versions/game)
+#[derive(Deserialize)]
+pub(super) struct FabricGameVersion {
+    pub(super) version: String,
+}
+
+/// ID профиля Fabric в том виде, в котором его отдаёт Fabric meta
+pub fn fabric_profile_id(game_version: &str, loader_version: &str) -> String {
+    format!("fabric-loader-{loader_version}-{game_version}")
+}
+
+impl ClientInstaller {
+    /// ID версии, которую ставит и запускает лаунчер по умолчанию
+    pub fn default_version_id() -> String {
+        fabric_profile_id(MINECRAFT_VERSION, FABRIC_LOADER_VERSION)
+    }
+
+    /// Версии Fabric Loader, совместимые с версией игры (новые первыми)
+    pub async fn fabric_loader_versions(
+        &self,
+        game_version: &str,
+    ) -> Result<Vec<FabricLoaderVersion>> {
+        let url = format!(
+            "{}/v2/versions/loader/{}",
+            self.endpoints.fabric_meta_url, game_version
+        );
+        let entries: Vec<GameLoaderEntry> = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Не удалось подключиться к Fabric meta")?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Ошибка декодирования списка версий Fabric Loader")?;
+
+        Ok(entries.into_iter().map(|e| e.loader).collect())
+    }
+
+    /// Версии игры, для которых есть Fabric
+    pub(super) async fn fabric_game_versions(&self) -> Result<Vec<FabricGameVersion>> {
+        let url = format!("{}/v2/versions/game", self.endpoints.fabric_meta_url);
+        self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Не удалось подключиться к Fabric meta")?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Ошибка декодирования списка версий игры Fabric")
+    }
+
+    /// Все версии Fabric Loader (новые первыми). Fabric meta считает каждую из них
+    /// совместимой с любой версией из `fabric_game_versions`.
+    pub(super) async fn fabric_all_loader_versions(&self) -> Result<Vec<FabricLoaderVersion>> {
+        let url = format!("{}/v2/versions/loader", self.endpoints.fabric_meta_url);
+        self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Не удалось подключиться к Fabric meta")?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Ошибка декодирования списка версий Fabric Loader")
+    }
+
+    /// Ставит профиль Fabric поверх vanilla версии: пишет version JSON в versions/
+    /// и качает Maven-библиотеки загрузчика. Возвращает ID профиля.
+    /// Если версия загрузчика не указана, берётся последняя стабильная.
+    pub async fn install_fabric_profile<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        game_version: &str,
+        loader_version: Option<&str>,
+    ) -> Result<String> {
+        let _ = app.emit(
+            "client-install-progress",
+            InstallProgress {
+                stage: "fabric".to_string(),
+                progress: 0.0,
+                message: "Подготовка загрузчика...".to_string(),
+            },
+        );
+
+        let loader_version = match loader_version {
+            Some(v) => v.to_string(),
+            None => self
+                .fabric_loader_versions(game_version)
+                .await?
+                .into_iter()
+                .find(|v| v.stable)
+                .map(|v| v.version)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Нет стабильного Fabric Loader для {}", game_version)
+                })?,
+        };
+
+        let url = format!(
+            "{}/v2/versions/loader/{}/{}/profile/json",
+            self.endpoints.fabric_meta_url, game_version, loader_version
+        );
+        let profile_raw = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Не удалось получить профиль Fabric")?
+            .error_for_status()?
+            .text()
+            .await?;
+        let profile: VersionJson = parse_json(&profile_raw, "Fabric profile")?;
+
+        let profile_id = if profile.id.is_empty() {
+            fabric_profile_id(game_version, &loader_version)
+        } else {
+            profile.id.clone()
+        };
+
+        self.install_libraries(app, &profile).await?;
+
+        // JSON пишем последним: его наличие означает, что профиль установлен целиком
+        let profile_dir = self.launch_dir.join("versions").join(&profile_id);
+        fs::create_dir_all(&profile_dir)?;
+        fs::write(profile_dir.join(format!("{profile_id}.json")), profile_raw)?;
+
+        let _ = app.emit(
+            "client-install-progress",
+            InstallProgress {
+                stage: "fabric".to_string(),
+                progress: 100.0,
+                message: format!("Загрузчик Fabric {} установлен", loader_version),
+            },
+        );
+
+        Ok(profile_id)
+    }
+}
@@ -1,23 +1,124 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use serde::Deserialize;
 use tauri::{AppHandle, Emitter, Runtime};
 
+use super::download::DownloadTask;
 use super::{ClientInstaller, InstallProgress};
+use crate::minecraft::model::{parse_json, Download, JavaVersion};
 
-// Adoptium (Eclipse Temurin) Java 21 URLs
-#[cfg(target_os = "windows")]
-const JAVA_21_URL: &str = "https://github.com/adoptium/temurin21-binaries/releases/download/jdk-21.0.5%2B11/OpenJDK21U-jre_x64_windows_hotspot_21.0.5_11.zip";
+/// Файл-метка полностью установленного runtime (внутри него — версия Java)
+const RUNTIME_MARKER: &str = ".version";
 
-#[cfg(target_os = "linux")]
-const JAVA_21_URL: &str = "https://github.com/adoptium/temurin21-binaries/releases/download/jdk-21.0.5%2B11/OpenJDK21U-jre_x64_linux_hotspot_21.0.5_11.tar.gz";
+/// Java, которую ставит команда install_java и проверяет check_java_status
+fn default_java() -> JavaVersion {
+    JavaVersion {
+        component: Some("java-runtime-delta".to_string()),
+        major_version: 21,
+    }
+}
+
+/// Платформа в терминах Mojang runtime manifest
+fn runtime_platform() -> Option<&'static str> {
+    if cfg!(target_os = "windows") {
+        if cfg!(target_arch = "x86_64") {
+            Some("windows-x64")
+        } else if cfg!(target_arch = "x86") {
+            Some("windows-x86")
+        } else if cfg!(target_arch = "aarch64") {
+            Some("windows-arm64")
+        } else {
+            None
+        }
+    } else if cfg!(target_os = "linux") {
+        if cfg!(target_arch = "x86_64") {
+            Some("linux")
+        } else if cfg!(target_arch = "x86") {
+            Some("linux-i386")
+        } else {
+            None
+        }
+    } else if cfg!(target_os = "macos") {
+        if cfg!(target_arch = "aarch64") {
+            Some("mac-os-arm64")
+        } else {
+            Some("mac-os")
+        }
+    } else {
+        None
+    }
+}
+
+/// all.json: платформа -> компонент -> сборки
+type RuntimeIndex = HashMap<String, HashMap<String, Vec<RuntimeEntry>>>;
+
+#[derive(Deserialize)]
+struct RuntimeEntry {
+    manifest: Download,
+    version: RuntimeVersion,
+}
+
+#[derive(Deserialize)]
+struct RuntimeVersion {
+    name: String,
+}
+
+/// Манифест файлов одного runtime
+#[derive(Deserialize)]
+struct RuntimeManifest {
+    files: HashMap<String, RuntimeFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum RuntimeFile {
+    Directory,
+    File {
+        #[serde(default)]
+        #[cfg_attr(not(unix), allow(dead_code))]
+        executable: bool,
+        downloads: RuntimeFileDownloads,
+    },
+    Link {
+        #[cfg_attr(not(unix), allow(dead_code))]
+        target: String,
+    },
+}
 
-#[cfg(target_os = "macos")]
-const JAVA_21_URL: &str = "https://github.com/adoptium/temurin21-binaries/releases/download/jdk-21.0.5%2B11/OpenJDK21U-jre_x64_mac_hotspot_21.0.5_11.tar.gz";
+#[derive(Deserialize)]
+struct RuntimeFileDownloads {
+    raw: Download,
+}
+
+/// Старший номер версии из вывода `java -version` ("1.8.0_392" -> 8, "21.0.5" -> 21)
+fn parse_java_major(version_output: &str) -> Option<u32> {
+    let start = version_output.find('"')? + 1;
+    let end = version_output[start..].find('"')? + start;
+    let version = &version_output[start..end];
+
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    let first: u32 = parts.next()?.parse().ok()?;
+    if first == 1 {
+        parts.next()?.parse().ok()
+    } else {
+        Some(first)
+    }
+}
 
-const JAVA_VERSION: &str = "21.0.5";
+/// Старший номер версии Java по исполняемому файлу
+pub(crate) fn java_major_version(java_path: &Path) -> Option<u32> {
+    parse_java_major(&java_version_output(java_path)?)
+}
+
+/// Версия Java из `java -version` (Java пишет её в stderr)
+fn java_version_output(java_path: &Path) -> Option<String> {
+    let output = Command::new(java_path).arg("-version").output().ok()?;
+    Some(String::from_utf8_lossy(&output.stderr).to_string())
+}
 
 impl ClientInstaller {
     /// Возвращает путь к директории Java
@@ -25,234 +126,286 @@ impl ClientInstaller {
         self.base_dir.join("java")
     }
 
-    /// Возвращает путь к исполняемому файлу Java
-    pub fn get_java_executable(&self) -> PathBuf {
-        let java_dir = self.get_java_dir();
-        
-        #[cfg(target_os = "windows")]
-        {
-            // Ищем java.exe в подпапках
-            if let Some(java_path) = Self::find_java_in_dir(&java_dir) {
-                return java_path;
-            }
-            java_dir.join("bin").join("java.exe")
-        }
-        
-        #[cfg(not(target_os = "windows"))]
-        {
-            if let Some(java_path) = Self::find_java_in_dir(&java_dir) {
-                return java_path;
-            }
-            java_dir.join("bin").join("java")
-        }
+    /// Директория runtime компонента: java/<component>/
+    pub fn get_runtime_dir(&self, component: &str) -> PathBuf {
+        self.get_java_dir().join(component)
     }
 
-    /// Ищет java executable в директории (рекурсивно на 2 уровня)
-    fn find_java_in_dir(dir: &Path) -> Option<PathBuf> {
-        if !dir.exists() {
-            return None;
-        }
+    /// Исполняемый файл Java внутри runtime компонента
+    pub fn get_runtime_executable(&self, component: &str) -> PathBuf {
+        let runtime_dir = self.get_runtime_dir(component);
 
         #[cfg(target_os = "windows")]
-        let java_name = "java.exe";
-        #[cfg(not(target_os = "windows"))]
-        let java_name = "java";
-
-        // Проверяем bin/java напрямую
-        let direct_path = dir.join("bin").join(java_name);
-        if direct_path.exists() {
-            return Some(direct_path);
+        {
+            runtime_dir.join("bin").join("java.exe")
         }
 
-        // Ищем в подпапках (например jdk-21.0.5+11-jre/bin/java)
-        if let Ok(entries) = fs::read_dir(dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_dir() {
-                    let java_path = path.join("bin").join(java_name);
-                    if java_path.exists() {
-                        return Some(java_path);
-                    }
-                }
-            }
+        #[cfg(target_os = "macos")]
+        {
+            runtime_dir
+                .join("jre.bundle")
+                .join("Contents")
+                .join("Home")
+                .join("bin")
+                .join("java")
         }
 
-        None
-    }
-
-    /// Проверяет, установлена ли Java 21
-    pub fn is_java_installed(&self) -> bool {
-        let java_exe = self.get_java_executable();
-        if !java_exe.exists() {
-            return false;
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        {
+            runtime_dir.join("bin").join("java")
         }
+    }
 
-        // Проверяем версию
-        self.check_java_version(&java_exe).unwrap_or(false)
+    /// Возвращает путь к исполняемому файлу Java по умолчанию
+    pub fn get_java_executable(&self) -> PathBuf {
+        let java = default_java();
+        self.get_runtime_executable(java.component_name().unwrap_or_default())
     }
 
-    /// Проверяет версию Java
-    fn check_java_version(&self, java_path: &Path) -> Result<bool> {
-        let output = Command::new(java_path)
-            .arg("-version")
-            .output()?;
+    /// Установлен ли runtime компонента целиком
+    pub fn is_runtime_installed(&self, component: &str) -> bool {
+        self.get_runtime_dir(component)
+            .join(RUNTIME_MARKER)
+            .exists()
+            && self.get_runtime_executable(component).exists()
+    }
 
-        let version_output = String::from_utf8_lossy(&output.stderr);
-        
-        // Java выводит версию в stderr в формате: openjdk version "21.0.5" или java version "21.0.5"
-        Ok(version_output.contains("21.0") || version_output.contains("\"21."))
+    /// Проверяет, установлена ли Java по умолчанию
+    pub fn is_java_installed(&self) -> bool {
+        let java = default_java();
+        self.is_runtime_installed(java.component_name().unwrap_or_default())
     }
 
-    /// Проверяет системную Java
-    pub fn check_system_java() -> Option<String> {
-        let output = Command::new("java")
-            .arg("-version")
-            .output()
-            .ok()?;
-
-        let version_output = String::from_utf8_lossy(&output.stderr);
-        
-        // Проверяем что это Java 21
-        if version_output.contains("21.0") || version_output.contains("\"21.") {
+    /// Системная Java нужной версии. `java -version` запускается вне async runtime.
+    pub async fn find_system_java(major_version: u32) -> Option<String> {
+        let output = tokio::task::spawn_blocking(|| java_version_output(Path::new("java")))
+            .await
+            .ok()??;
+        if parse_java_major(&output) == Some(major_version) {
             return Some("java".to_string());
         }
 
         None
     }
 
-    /// Устанавливает Java 21 если не установлена
+    /// Проверяет системную Java
+    pub async fn check_system_java() -> Option<String> {
+        Self::find_system_java(default_java().major_version).await
+    }
+
+    /// Устанавливает Java по умолчанию если не установлена
     pub async fn ensure_java_installed<R: Runtime>(&self, app: &AppHandle<R>) -> Result<String> {
-        // Сначала проверяем нашу локальную Java
-        if self.is_java_installed() {
-            let java_path = self.get_java_executable();
-            log::info!("Java 21 already installed at: {:?}", java_path);
-            return Ok(java_path.to_string_lossy().to_string());
+        self.ensure_java_runtime(app, &default_java()).await
+    }
+
+    /// Уже установленная Java для версии игры: свой runtime из java/<component>/,
+    /// затем системная Java той же версии
+    pub async fn find_java_runtime(&self, java: &JavaVersion) -> Option<String> {
+        let major = java.major_version;
+
+        if let Some(component) = java.component_name() {
+            if self.is_runtime_installed(component) {
+                let java_path = self.get_runtime_executable(component);
+                log::info!("Java {} already installed at: {:?}", major, java_path);
+                return Some(java_path.to_string_lossy().to_string());
+            }
         }
 
-        // Проверяем системную Java
-        if let Some(system_java) = Self::check_system_java() {
-            log::info!("Using system Java 21");
-            return Ok(system_java);
+        let system_java = Self::find_system_java(major).await?;
+        log::info!("Using system Java {}", major);
+        Some(system_java)
+    }
+
+    /// Java для версии игры: свой runtime из java/<component>/, затем системная Java
+    /// той же версии, иначе runtime ставится из Mojang runtime manifest.
+    pub async fn ensure_java_runtime<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        java: &JavaVersion,
+    ) -> Result<String> {
+        let major = java.major_version;
+        self.remove_legacy_java();
+
+        if let Some(java_path) = self.find_java_runtime(java).await {
+            return Ok(java_path);
         }
 
-        // Устанавливаем Java
-        log::info!("Java 21 not found, installing...");
-        self.install_java(app).await?;
+        let component = java.component_name().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Java {} is not available from Mojang, install it manually",
+                major
+            )
+        })?;
 
-        let java_path = self.get_java_executable();
+        log::info!("Java {} not found, installing {}...", major, component);
+        self.install_java_runtime(app, component).await?;
+
+        let java_path = self.get_runtime_executable(component);
         if java_path.exists() {
             Ok(java_path.to_string_lossy().to_string())
         } else {
-            Err(anyhow::anyhow!("Failed to install Java 21"))
+            Err(anyhow::anyhow!("Failed to install Java {}", major))
         }
     }
 
-    /// Скачивает и устанавливает Java 21
-    async fn install_java<R: Runtime>(&self, app: &AppHandle<R>) -> Result<()> {
-        let _ = app.emit(
-            "client-install-progress",
-            InstallProgress {
-                stage: "java".to_string(),
-                progress: 0.0,
-                message: "Скачивание Java 21...".to_string(),
-            },
-        );
-
+    /// Удаляет Temurin из прежней раскладки: он распаковывался прямо в java/
+    /// (bin/ или jdk-*/) и новыми версиями лаунчера не используется
+    fn remove_legacy_java(&self) {
         let java_dir = self.get_java_dir();
-        fs::create_dir_all(&java_dir)?;
-
-        #[cfg(target_os = "windows")]
-        let archive_name = "java.zip";
-        #[cfg(not(target_os = "windows"))]
-        let archive_name = "java.tar.gz";
-
-        let archive_path = self.base_dir.join(archive_name);
+        let entries = match fs::read_dir(&java_dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+            // Компоненты Mojang называются java-runtime-* и jre-legacy
+            let legacy = name.starts_with("jdk-")
+                || ["bin", "lib", "conf", "legal", "release", "NOTICE"].contains(&name.as_str());
+            if !legacy {
+                continue;
+            }
+            let removed = if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            };
+            match removed {
+                Ok(()) => log::info!("Removed legacy Java files: {:?}", path),
+                Err(e) => log::warn!("Failed to remove legacy Java {:?}: {}", path, e),
+            }
+        }
 
-        // Скачиваем архив
-        self.download_file_force(JAVA_21_URL, &archive_path, app, "Java", true)
-            .await?;
+        for archive in ["java.zip", "java.tar.gz"] {
+            let _ = fs::remove_file(self.base_dir.join(archive));
+        }
+    }
 
+    /// Скачивает runtime компонента по Mojang runtime manifest в java/<component>/
+    async fn install_java_runtime<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        component: &str,
+    ) -> Result<()> {
         let _ = app.emit(
             "client-install-progress",
             InstallProgress {
                 stage: "java".to_string(),
-                progress: 70.0,
-                message: "Распаковка Java 21...".to_string(),
+                progress: 0.0,
+                message: format!("Получение Java ({})...", component),
             },
         );
 
-        // Распаковываем
-        #[cfg(target_os = "windows")]
-        self.extract_java_zip(&archive_path, &java_dir)?;
+        let platform = runtime_platform()
+            .ok_or_else(|| anyhow::anyhow!("Mojang не публикует Java для этой платформы"))?;
+
+        let index_raw = self
+            .client
+            .get(&self.endpoints.java_runtime_manifest_url)
+            .send()
+            .await
+            .context("Не удалось получить список Java от Mojang")?
+            .error_for_status()?
+            .text()
+            .await?;
+        let index: RuntimeIndex = parse_json(&index_raw, "Java runtime index")?;
+
+        let entry = index
+            .get(platform)
+            .and_then(|components| components.get(component))
+            .and_then(|entries| entries.first())
+            .ok_or_else(|| {
+                anyhow::anyhow!("Java {} недоступна для платформы {}", component, platform)
+            })?;
+
+        let manifest_raw = self
+            .client
+            .get(&entry.manifest.url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let manifest: RuntimeManifest = parse_json(
+            &manifest_raw,
+            &format!("Java runtime manifest {}", component),
+        )?;
+
+        let runtime_dir = self.get_runtime_dir(component);
+        // Метку убираем заранее: прерванная установка не должна считаться готовой
+        let _ = fs::remove_file(runtime_dir.join(RUNTIME_MARKER));
+        fs::create_dir_all(&runtime_dir)?;
+
+        let mut tasks = Vec::new();
+        for (path, file) in &manifest.files {
+            let target = path
+                .split('/')
+                .fold(runtime_dir.clone(), |dir, part| dir.join(part));
+            match file {
+                RuntimeFile::Directory => fs::create_dir_all(&target)?,
+                RuntimeFile::File { downloads, .. } => {
+                    tasks.push(DownloadTask::from_download(&downloads.raw, &target));
+                }
+                RuntimeFile::Link { .. } => {}
+            }
+        }
+
+        self.download_all(tasks, app, "java", "Скачивание Java")
+            .await?;
 
-        #[cfg(not(target_os = "windows"))]
-        self.extract_java_tar_gz(&archive_path, &java_dir)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            // Права на запуск и ссылки нужны только unix-сборкам
+            for (path, file) in &manifest.files {
+                let target_path = path
+                    .split('/')
+                    .fold(runtime_dir.clone(), |dir, part| dir.join(part));
+                match file {
+                    RuntimeFile::File {
+                        executable: true, ..
+                    } => fs::set_permissions(&target_path, fs::Permissions::from_mode(0o755))?,
+                    RuntimeFile::Link { target } if target_path.symlink_metadata().is_err() => {
+                        std::os::unix::fs::symlink(target, &target_path)?
+                    }
+                    _ => {}
+                }
+            }
+        }
 
-        // Удаляем архив
-        let _ = fs::remove_file(&archive_path);
+        fs::write(runtime_dir.join(RUNTIME_MARKER), &entry.version.name)?;
 
         let _ = app.emit(
             "client-install-progress",
             InstallProgress {
                 stage: "java".to_string(),
                 progress: 100.0,
-                message: "Java 21 установлена".to_string(),
+                message: format!("Java {} установлена", entry.version.name),
             },
         );
 
-        log::info!("Java 21 installed successfully");
-        Ok(())
-    }
-
-    #[cfg(target_os = "windows")]
-    fn extract_java_zip(&self, archive_path: &Path, dest_dir: &Path) -> Result<()> {
-        let file = fs::File::open(archive_path)?;
-        let mut archive = zip::ZipArchive::new(file)?;
-        archive.extract(dest_dir)?;
-        Ok(())
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    fn extract_java_tar_gz(&self, archive_path: &Path, dest_dir: &Path) -> Result<()> {
-        use std::process::Command;
-        
-        let status = Command::new("tar")
-            .args(["-xzf", &archive_path.to_string_lossy(), "-C", &dest_dir.to_string_lossy()])
-            .status()?;
-
-        if !status.success() {
-            return Err(anyhow::anyhow!("Failed to extract Java archive"));
-        }
-
+        log::info!(
+            "Java runtime {} {} installed",
+            component,
+            entry.version.name
+        );
         Ok(())
     }
 
-    /// Возвращает версию установленной Java
+    /// Возвращает версию установленной Java по умолчанию
     pub fn get_installed_java_version(&self) -> Option<String> {
         let java_exe = self.get_java_executable();
         if !java_exe.exists() {
             return None;
         }
 
-        let output = Command::new(&java_exe)
-            .arg("-version")
-            .output()
-            .ok()?;
-
-        let version_output = String::from_utf8_lossy(&output.stderr);
-        
-        // Парсим версию из вывода
-        for line in version_output.lines() {
-            if line.contains("version") {
-                // Ищем версию в кавычках
-                if let Some(start) = line.find('"') {
-                    if let Some(end) = line[start + 1..].find('"') {
-                        return Some(line[start + 1..start + 1 + end].to_string());
-                    }
-                }
-            }
-        }
+        let version_output = java_version_output(&java_exe)?;
 
-        Some(JAVA_VERSION.to_string())
+        // Ищем версию в кавычках: openjdk version "21.0.5"
+        let start = version_output.find('"')? + 1;
+        let end = version_output[start..].find('"')? + start;
+        Some(version_output[start..end].to_string())
     }
 }
@@ -1,10 +1,13 @@
-use std::fs;
 use std::path::Path;
 
 use anyhow::Result;
 use tauri::{AppHandle, Emitter, Runtime};
 
-use super::{ClientInstaller, InstallProgress, LAUNCH_ZIP_URL};
+use super::{ClientInstaller, InstallProgress, FABRIC_LOADER_VERSION, MINECRAFT_VERSION};
+use crate::minecraft::version::resolve_version_jar;
+
+/// Профиль из прежнего launch.zip: у существующих установок игра лежит под этим ID
+const LEGACY_VERSION_ID: &str = "Fabric 1.21.4";
 
 impl ClientInstaller {
     pub(crate) async fn install_launch_files<R: Runtime>(&self, app: &AppHandle<R>) -> Result<()> {
@@ -17,45 +20,7 @@ impl ClientInstaller {
             },
         );
 
-        let launch_zip_path = self.base_dir.join("launch.zip");
-
-        // Проверяем, установлены ли уже runtime файлы (libraries/assets/versions)
-        // Не привязываемся к конкретному version_id, т.к. он может отличаться в архиве.
-        let assets_dir = self.launch_dir.join("assets");
-        let libraries_dir = self.launch_dir.join("libraries");
-        let versions_dir = self.launch_dir.join("versions");
-
-        let has_any_version = if let Ok(entries) = fs::read_dir(&versions_dir) {
-            entries
-                .flatten()
-                .filter(|e| e.path().is_dir())
-                .any(|e| {
-                    let dir = e.path();
-                    if let Ok(files) = fs::read_dir(&dir) {
-                        let mut has_json = false;
-                        let mut has_jar = false;
-                        for f in files.flatten() {
-                            let name = f.file_name().to_string_lossy().to_lowercase();
-                            if name.ends_with(".json") {
-                                has_json = true;
-                            }
-                            if name.ends_with(".jar") {
-                                has_jar = true;
-                            }
-                            if has_json && has_jar {
-                                return true;
-                            }
-                        }
-                    }
-                    false
-                })
-        } else {
-            false
-        };
-
-        let runtime_exists = assets_dir.exists() && libraries_dir.exists() && has_any_version;
-
-        if runtime_exists {
+        if self.is_launch_runtime_installed() {
             let _ = app.emit(
                 "client-install-progress",
                 InstallProgress {
@@ -67,70 +32,11 @@ impl ClientInstaller {
             return Ok(());
         }
 
-        // Скачиваем launch.zip (принудительно, если файл повреждён)
-        self.download_file_force(LAUNCH_ZIP_URL, &launch_zip_path, app, "Game", true)
+        // Сначала vanilla (jar, библиотеки, ассеты), затем профиль Fabric поверх неё
+        self.install_vanilla_version(app, MINECRAFT_VERSION).await?;
+        self.install_fabric_profile(app, MINECRAFT_VERSION, Some(FABRIC_LOADER_VERSION))
             .await?;
 
-        let _ = app.emit(
-            "client-install-progress",
-            InstallProgress {
-                stage: "launch".to_string(),
-                progress: 70.0,
-                message: "Оптимизация файлов...".to_string(),
-            },
-        );
-
-        // Проверяем, что файл является валидным ZIP
-        let file = fs::File::open(&launch_zip_path)?;
-        let archive_result = zip::ZipArchive::new(file);
-        
-        let mut archive = match archive_result {
-            Ok(a) => a,
-            Err(e) => {
-                // Удаляем повреждённый файл
-                let _ = fs::remove_file(&launch_zip_path);
-                return Err(anyhow::anyhow!(
-                    "Ошибка: скачанный архив повреждён ({}). Попробуйте переустановить клиент.", 
-                    e
-                ));
-            }
-        };
-
-        // Распаковываем во временную директорию
-        let temp_extract_dir = self.base_dir.join("temp_launch");
-        if temp_extract_dir.exists() {
-            fs::remove_dir_all(&temp_extract_dir)?;
-        }
-        fs::create_dir_all(&temp_extract_dir)?;
-        archive.extract(&temp_extract_dir)?;
-
-        // Ищем папку с файлами (может быть launchMode или другая)
-        let mut source_dir = None;
-        if let Ok(entries) = fs::read_dir(&temp_extract_dir) {
-            for entry in entries.flatten() {
-                if entry.path().is_dir() {
-                    // Проверяем, что это папка с runtime-контентом (versions/libraries)
-                    let versions = entry.path().join("versions");
-                    let libraries = entry.path().join("libraries");
-
-                    if versions.exists() && libraries.exists() {
-                        source_dir = Some(entry.path());
-                        break;
-                    }
-                }
-            }
-        }
-
-        // Если не нашли подпапку, используем саму temp директорию
-        let source = source_dir.unwrap_or(temp_extract_dir.clone());
-
-        // Копируем файлы из source в launch_dir
-        self.copy_dir_contents(&source, &self.launch_dir)?;
-
-        // Удаляем временные файлы
-        fs::remove_dir_all(temp_extract_dir)?;
-        fs::remove_file(launch_zip_path)?;
-
         let _ = app.emit(
             "client-install-progress",
             InstallProgress {
@@ -143,30 +49,25 @@ impl ClientInstaller {
         Ok(())
     }
 
-    fn copy_dir_contents(&self, src: &Path, dst: &Path) -> Result<()> {
-        if !dst.exists() {
-            fs::create_dir_all(dst)?;
-        }
-
-        for entry in fs::read_dir(src)? {
-            let entry = entry?;
-            let src_path = entry.path();
-            let file_name = entry.file_name();
-
-            // Skip the 'run' directory completely - we manage it separately for mods
-            if file_name == "run" {
-                continue;
-            }
-
-            let dst_path = dst.join(&file_name);
-
-            if src_path.is_dir() {
-                self.copy_dir_contents(&src_path, &dst_path)?;
-            } else {
-                fs::copy(&src_path, &dst_path)?;
-            }
-        }
+    /// Проверяет, что профиль по умолчанию установлен вместе с jar и ассетами
+    pub(crate) fn is_launch_runtime_installed(&self) -> bool {
+        Self::installed_default_version_id(&self.launch_dir).is_some()
+            && self.launch_dir.join("assets").join("indexes").exists()
+            && self.launch_dir.join("libraries").exists()
+    }
 
-        Ok(())
+    /// Установленный профиль лаунчера: текущий или, если его ещё нет, из прежнего launch.zip,
+    /// чтобы обновление не переустанавливало игру целиком
+    pub(crate) fn installed_default_version_id(launch_dir: &Path) -> Option<String> {
+        [Self::default_version_id(), LEGACY_VERSION_ID.to_string()]
+            .into_iter()
+            .find(|version_id| {
+                launch_dir
+                    .join("versions")
+                    .join(version_id)
+                    .join(format!("{version_id}.json"))
+                    .exists()
+                    && resolve_version_jar(launch_dir, version_id).is_some()
+            })
     }
 }
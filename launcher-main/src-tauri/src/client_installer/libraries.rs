@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Result;
+use tauri::{AppHandle, Runtime};
+
+use super::download::{is_file_present, DownloadTask};
+use super::ClientInstaller;
+use crate::minecraft::classpath::{is_library_allowed, library_file};
+use crate::minecraft::model::{Artifact, Library, VersionJson};
+use crate::minecraft::rules::Platform;
+
+/// Задача скачивания из `downloads.artifact` / `downloads.classifiers`
+fn artifact_task(libraries_dir: &Path, artifact: &Artifact, path: &str) -> DownloadTask {
+    DownloadTask {
+        url: artifact.url.clone(),
+        dest: library_file(libraries_dir, path),
+        sha1: artifact.sha1.clone(),
+        size: artifact.size,
+    }
+}
+
+/// Задача скачивания из Maven-репозитория: `<url><maven path>` (библиотеки Fabric и старые записи).
+/// Без `url` берётся `default_repo`.
+fn maven_task(libraries_dir: &Path, default_repo: &str, lib: &Library, path: &str) -> DownloadTask {
+    let repo = lib.url.as_deref().unwrap_or(default_repo);
+    DownloadTask {
+        url: format!("{}/{}", repo.trim_end_matches('/'), path),
+        dest: library_file(libraries_dir, path),
+        sha1: lib.sha1.clone(),
+        size: lib.size,
+    }
+}
+
+/// Файлы библиотеки для платформы: основной jar и jar с нативами старого формата
+fn library_tasks(
+    libraries_dir: &Path,
+    default_repo: &str,
+    lib: &Library,
+    platform: &Platform,
+) -> Vec<DownloadTask> {
+    let mut tasks = Vec::new();
+
+    let artifact = lib.downloads.as_ref().and_then(|d| d.artifact.as_ref());
+    match artifact {
+        Some(artifact) if !artifact.url.is_empty() => {
+            if let Some(path) = lib.artifact_path() {
+                tasks.push(artifact_task(libraries_dir, artifact, &path));
+            }
+        }
+        // У записей только с `natives` основного jar нет
+        _ if lib.is_natives_only() => {}
+        _ => {
+            if let Some(path) = lib.artifact_path() {
+                tasks.push(maven_task(libraries_dir, default_repo, lib, &path));
+            }
+        }
+    }
+
+    if let Some(path) = lib.native_artifact_path(platform) {
+        match lib.native_artifact(platform) {
+            Some(native) if !native.url.is_empty() => {
+                tasks.push(artifact_task(libraries_dir, native, &path))
+            }
+            _ => tasks.push(maven_task(libraries_dir, default_repo, lib, &path)),
+        }
+    }
+
+    tasks
+}
+
+impl ClientInstaller {
+    /// Докачивает недостающие библиотеки версии с проверкой SHA-1/размера.
+    /// Файлы нужного размера считаются целыми: SHA-1 проверяется только у скачиваемых,
+    /// иначе каждый запуск хешировал бы все библиотеки.
+    /// Если что-то скачать не удалось, возвращает ошибку со списком таких библиотек.
+    pub async fn install_libraries<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        version_json: &VersionJson,
+    ) -> Result<()> {
+        let libraries_dir = self.launch_dir.join("libraries");
+        let platform = Platform::current();
+        let default_repo = &self.endpoints.libraries_url;
+
+        let mut names = HashMap::new();
+        let mut tasks = Vec::new();
+        for lib in &version_json.libraries {
+            if !is_library_allowed(lib, platform) {
+                continue;
+            }
+            // После мержа inheritsFrom одна и та же библиотека может встретиться дважды
+            for task in library_tasks(&libraries_dir, default_repo, lib, platform) {
+                if names.contains_key(&task.dest) || is_file_present(&task.dest, task.size) {
+                    continue;
+                }
+                names.insert(task.dest.clone(), lib.name.clone());
+                tasks.push(task);
+            }
+        }
+
+        let failures = self
+            .download_each(tasks, app, "libraries", "Скачивание библиотек")
+            .await;
+        if failures.is_empty() {
+            return Ok(());
+        }
+
+        let list: Vec<String> = failures
+            .iter()
+            .map(|(task, e)| {
+                let name = names.get(&task.dest).map(String::as_str).unwrap_or("?");
+                format!("  {} ({}): {}", name, task.url, e)
+            })
+            .collect();
+        Err(anyhow::anyhow!(
+            "Failed to download {} libraries:\n{}",
+            failures.len(),
+            list.join("\n")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library(json: &str) -> Library {
+        serde_json::from_str(json).expect("valid library")
+    }
+
+    fn urls(lib: &Library, platform: &Platform) -> Vec<String> {
+        library_tasks(
+            Path::new("libraries"),
+            "https://repo.example/",
+            lib,
+            platform,
+        )
+        .into_iter()
+        .map(|task| task.url)
+        .collect()
+    }
+
+    #[test]
+    fn maven_natives_only_library_downloads_just_the_natives_jar() {
+        let lib = library(
+            r#"{"name": "org.lwjgl.lwjgl:lwjgl-platform:2.9.4", "natives": {"linux": "natives-linux"}}"#,
+        );
+        let linux = Platform::new("linux", "x86_64", "");
+
+        assert!(lib.is_natives_only());
+        assert_eq!(
+            urls(&lib, &linux),
+            ["https://repo.example/org/lwjgl/lwjgl/lwjgl-platform/2.9.4/lwjgl-platform-2.9.4-natives-linux.jar"]
+        );
+        // На платформе без нативов у записи нечего качать
+        assert!(urls(&lib, &Platform::new("osx", "x86_64", "")).is_empty());
+    }
+
+    #[test]
+    fn library_with_artifact_and_natives_downloads_both() {
+        let lib = library(
+            r#"{
+                "name": "org.lwjgl:lwjgl:3.2.2",
+                "natives": {"linux": "natives-linux"},
+                "downloads": {
+                    "artifact": {"path": "org/lwjgl/lwjgl/3.2.2/lwjgl-3.2.2.jar", "url": "https://libs.example/lwjgl.jar"},
+                    "classifiers": {
+                        "natives-linux": {"path": "org/lwjgl/lwjgl/3.2.2/lwjgl-3.2.2-natives-linux.jar", "url": "https://libs.example/lwjgl-natives.jar"}
+                    }
+                }
+            }"#,
+        );
+
+        assert!(!lib.is_natives_only());
+        assert_eq!(
+            urls(&lib, &Platform::new("linux", "x86_64", "")),
+            [
+                "https://libs.example/lwjgl.jar",
+                "https://libs.example/lwjgl-natives.jar"
+            ]
+        );
+    }
+}
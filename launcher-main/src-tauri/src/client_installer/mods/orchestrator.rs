@@ -22,7 +22,7 @@ impl ClientInstaller {
             },
         );
 
-        // Сначала устанавливаем launch файлы (gradlew и т.д.)
+        // Сначала устанавливаем игру и загрузчик
         self.install_launch_files(app).await?;
 
         // Затем устанавливаем моды
@@ -48,19 +48,7 @@ impl ClientInstaller {
 
     pub fn check_mods_installed(&self) -> bool {
         // Проверяем наличие runtime файлов
-        let version_id = "Fabric 1.21.4";
-        let version_dir = self.launch_dir.join("versions").join(version_id);
-        let version_json_path = version_dir.join(format!("{version_id}.json"));
-        let version_jar_path = version_dir.join(format!("{version_id}.jar"));
-        let assets_dir = self.launch_dir.join("assets");
-        let libraries_dir = self.launch_dir.join("libraries");
-
-        let runtime_exists = version_json_path.exists()
-            && version_jar_path.exists()
-            && assets_dir.exists()
-            && libraries_dir.exists();
-
-        if !runtime_exists {
+        if !self.is_launch_runtime_installed() {
             return false;
         }
 
@@ -0,0 +1,315 @@
+use anyhow::{Context, Result};
+use tauri::{AppHandle, Emitter, Runtime};
+
+use super::download::DownloadTask;
+use super::{ClientInstaller, InstallProgress};
+use crate::minecraft::model::{read_json_file, AssetIndex, VersionJson};
+
+impl ClientInstaller {
+    /// Устанавливает vanilla версию по данным Mojang: version JSON, client jar,
+    /// библиотеки, asset index и все asset-объекты.
+    pub async fn install_vanilla_version<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        version_id: &str,
+    ) -> Result<()> {
+        let _ = app.emit(
+            "client-install-progress",
+            InstallProgress {
+                stage: "version".to_string(),
+                progress: 0.0,
+                message: format!("Получение данных версии {}...", version_id),
+            },
+        );
+
+        let version_json_path = self
+            .manifest_client()
+            .ensure_version_json(version_id)
+            .await
+            .context("Не удалось получить данные версии Mojang")?;
+        let version_dir = self.launch_dir.join("versions").join(version_id);
+
+        let version_json = VersionJson::load(&version_json_path)?;
+
+        // Client jar
+        let client_download = version_json
+            .downloads
+            .as_ref()
+            .and_then(|d| d.get("client"))
+            .ok_or_else(|| anyhow::anyhow!("В {} нет downloads.client", version_id))?;
+        let jar_task = DownloadTask::from_download(
+            client_download,
+            &version_dir.join(format!("{version_id}.jar")),
+        );
+        self.download_all(vec![jar_task], app, "version", "Скачивание игры")
+            .await?;
+
+        // Библиотеки
+        self.install_libraries(app, &version_json).await?;
+
+        self.install_log_config(&version_json).await?;
+        self.install_assets(app, &version_json).await?;
+
+        let _ = app.emit(
+            "client-install-progress",
+            InstallProgress {
+                stage: "version".to_string(),
+                progress: 100.0,
+                message: format!("Версия {} установлена", version_id),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Скачивает конфиг логирования из `logging.client` в assets/log_configs/
+    pub async fn install_log_config(&self, version_json: &VersionJson) -> Result<()> {
+        let config = match version_json.logging.as_ref().and_then(|l| l.client.as_ref()) {
+            Some(config) => config,
+            None => return Ok(()),
+        };
+
+        self.download_verified(&DownloadTask {
+            url: config.file.url.clone(),
+            dest: config.config_path(&self.launch_dir.join("assets")),
+            sha1: config.file.sha1.clone(),
+            size: config.file.size,
+        })
+        .await
+    }
+
+    /// Скачивает asset index версии и все объекты из него
+    async fn install_assets<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        version_json: &VersionJson,
+    ) -> Result<()> {
+        let asset_index = version_json
+            .asset_index
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("В version JSON нет assetIndex"))?;
+
+        let assets_dir = self.launch_dir.join("assets");
+        let index_path = assets_dir
+            .join("indexes")
+            .join(format!("{}.json", asset_index.id));
+        self.download_verified(&DownloadTask {
+            url: asset_index.url.clone(),
+            dest: index_path.clone(),
+            sha1: asset_index.sha1.clone(),
+            size: asset_index.size,
+        })
+        .await?;
+
+        let index: AssetIndex = read_json_file(&index_path)?;
+
+        let objects_dir = assets_dir.join("objects");
+        let resources_url = self.endpoints.resources_url.trim_end_matches('/');
+
+        // Одинаковые хеши встречаются под разными именами — качаем каждый один раз
+        let mut objects: Vec<_> = index.objects.values().collect();
+        objects.sort_by(|a, b| a.hash.cmp(&b.hash));
+        objects.dedup_by(|a, b| a.hash == b.hash);
+
+        let tasks = objects
+            .into_iter()
+            .filter_map(|object| {
+                let object_path = object.object_path()?;
+                Some(DownloadTask {
+                    url: format!("{}/{}", resources_url, object_path),
+                    dest: objects_dir.join(&object_path),
+                    sha1: Some(object.hash.clone()),
+                    size: Some(object.size),
+                })
+            })
+            .collect();
+
+        self.download_all(tasks, app, "assets", "Скачивание ресурсов")
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+
+    use serde_json::json;
+
+    use super::super::{sha1_hex, ClientInstaller, Endpoints};
+
+    /// Минимальная замена Mojang: отдаёт файлы по точному пути, на остальное — 404
+    fn serve(listener: TcpListener, files: HashMap<String, Vec<u8>>) {
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+                loop {
+                    let mut header = String::new();
+                    match reader.read_line(&mut header) {
+                        Ok(n) if n > 0 && header != "\r\n" => {}
+                        _ => break,
+                    }
+                }
+
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+                let (status, body) = match files.get(path) {
+                    Some(body) => ("200 OK", body.as_slice()),
+                    None => ("404 Not Found", &b""[..]),
+                };
+                let head = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                let _ = stream.write_all(head.as_bytes());
+                let _ = stream.write_all(body);
+            }
+        });
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("launcher-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn download(base: &str, path: &str, data: &[u8]) -> serde_json::Value {
+        json!({ "url": format!("{base}{path}"), "sha1": sha1_hex(data), "size": data.len() })
+    }
+
+    #[tokio::test]
+    async fn installs_vanilla_version_from_stub_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+
+        let client_jar = b"client jar".to_vec();
+        let library_jar = b"library jar".to_vec();
+        let maven_jar = b"maven library jar".to_vec();
+        let sound = b"sound".to_vec();
+        let lang = b"lang".to_vec();
+
+        let asset_index = serde_json::to_vec(&json!({
+            "objects": {
+                "minecraft/sounds/click.ogg": { "hash": sha1_hex(&sound), "size": sound.len() },
+                "minecraft/lang/en_us.json": { "hash": sha1_hex(&lang), "size": lang.len() },
+                // Тот же объект под другим именем качается один раз
+                "minecraft/lang/en_gb.json": { "hash": sha1_hex(&lang), "size": lang.len() }
+            }
+        }))
+        .unwrap();
+
+        let library_path = "com/example/library/1.0/library-1.0.jar";
+        let version_json = serde_json::to_vec(&json!({
+            "id": "1.0-test",
+            "type": "release",
+            "mainClass": "net.minecraft.client.main.Main",
+            "assetIndex": {
+                "id": "test-index",
+                "url": format!("{base}/indexes/test-index.json"),
+                "sha1": sha1_hex(&asset_index),
+                "size": asset_index.len()
+            },
+            "downloads": { "client": download(&base, "/client.jar", &client_jar) },
+            "libraries": [
+                {
+                    "name": "com.example:library:1.0",
+                    "downloads": {
+                        "artifact": {
+                            "path": library_path,
+                            "url": format!("{base}/libraries/{library_path}"),
+                            "sha1": sha1_hex(&library_jar),
+                            "size": library_jar.len()
+                        }
+                    }
+                },
+                // Без downloads и url — из репозитория по умолчанию
+                { "name": "org.example:maven-library:2.0" }
+            ]
+        }))
+        .unwrap();
+
+        let manifest = serde_json::to_vec(&json!({
+            "latest": { "release": "1.0-test", "snapshot": "1.0-test" },
+            "versions": [{
+                "id": "1.0-test",
+                "type": "release",
+                "url": format!("{base}/v1/1.0-test.json"),
+                "sha1": sha1_hex(&version_json)
+            }]
+        }))
+        .unwrap();
+
+        let object = |data: &[u8]| {
+            let hash = sha1_hex(data);
+            format!("/resources/{}/{}", &hash[..2], hash)
+        };
+        let files = HashMap::from([
+            ("/manifest.json".to_string(), manifest),
+            ("/v1/1.0-test.json".to_string(), version_json),
+            ("/client.jar".to_string(), client_jar.clone()),
+            (format!("/libraries/{library_path}"), library_jar.clone()),
+            (
+                "/maven/org/example/maven-library/2.0/maven-library-2.0.jar".to_string(),
+                maven_jar.clone(),
+            ),
+            ("/indexes/test-index.json".to_string(), asset_index),
+            (object(&sound), sound.clone()),
+            (object(&lang), lang.clone()),
+        ]);
+        serve(listener, files);
+
+        let dir = temp_dir();
+        let endpoints = Endpoints {
+            version_manifest_url: format!("{base}/manifest.json"),
+            resources_url: format!("{base}/resources/"),
+            libraries_url: format!("{base}/maven/"),
+            java_runtime_manifest_url: format!("{base}/java.json"),
+            fabric_meta_url: format!("{base}/fabric"),
+        };
+        let installer = ClientInstaller::with_endpoints(dir.clone(), None, endpoints);
+        let app = tauri::test::mock_app();
+
+        installer
+            .install_vanilla_version(app.handle(), "1.0-test")
+            .await
+            .unwrap();
+
+        let launch = dir.join("launch");
+        let version_dir = launch.join("versions").join("1.0-test");
+        assert!(version_dir.join("1.0-test.json").is_file());
+        assert_eq!(
+            fs::read(version_dir.join("1.0-test.jar")).unwrap(),
+            client_jar
+        );
+        assert!(launch
+            .join("versions")
+            .join("version_manifest_v2.json")
+            .is_file());
+
+        let libraries = launch.join("libraries");
+        assert_eq!(fs::read(libraries.join(library_path)).unwrap(), library_jar);
+        assert_eq!(
+            fs::read(libraries.join("org/example/maven-library/2.0/maven-library-2.0.jar"))
+                .unwrap(),
+            maven_jar
+        );
+
+        let assets = launch.join("assets");
+        assert!(assets.join("indexes").join("test-index.json").is_file());
+        for data in [&sound, &lang] {
+            let hash = sha1_hex(data);
+            let path = assets.join("objects").join(&hash[..2]).join(&hash);
+            assert_eq!(&fs::read(path).unwrap(), data);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -20,6 +20,7 @@ pub fn run() {
     .manage(auth::AuthState {
         server_stop_tx: Mutex::new(None),
     })
+    .manage(minecraft::GameRegistry::default())
     .setup(|app| {
       log::info!("Boolean Launcher setup started");
       
@@ -42,11 +43,24 @@ pub fn run() {
         minecraft::check_client_updates,
         minecraft::install_mods,
         minecraft::install_and_launch,
+        minecraft::install_vanilla_version,
+        minecraft::list_installed_versions,
+        minecraft::list_available_versions,
+        minecraft::validate_installation,
         minecraft::get_client_dirs,
         minecraft::list_mods_folder,
         minecraft::wipe_client_data,
         minecraft::check_java_status,
         minecraft::install_java,
+        minecraft::list_running_games,
+        minecraft::kill_game,
+        minecraft::get_game_status,
+        minecraft::list_game_logs,
+        minecraft::read_game_log,
+        minecraft::search_game_log,
+        minecraft::get_total_playtime,
+        minecraft::get_last_played,
+        minecraft::list_recent_sessions,
         auth::start_oauth_server,
         auth::stop_oauth_server,
         auth::get_hwid
@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+
+use super::model::{Argument, ArgumentValue, Rule, RuleAction, VersionJson};
+use super::rules::{rules_allow, Features, Platform};
+
+/// Значения плейсхолдеров `${...}` из version.json
+pub type ArgumentVars = HashMap<&'static str, String>;
+
+fn plain(args: &[&str]) -> Vec<Argument> {
+    args.iter().map(|arg| Argument::Plain(arg.to_string())).collect()
+}
+
+/// Аргументы, которые включаются фичей лаунчера
+fn with_feature(feature: &str, args: &[&str]) -> Argument {
+    Argument::Conditional {
+        rules: vec![Rule {
+            action: RuleAction::Allow,
+            os: None,
+            features: Some(HashMap::from([(feature.to_string(), true)])),
+        }],
+        value: ArgumentValue::Many(args.iter().map(|arg| arg.to_string()).collect()),
+    }
+}
+
+/// JVM аргументы для версий без `arguments.jvm` (так делает официальный лаунчер)
+fn default_jvm_arguments() -> Vec<Argument> {
+    plain(&["-Djava.library.path=${natives_directory}", "-cp", "${classpath}"])
+}
+
+/// Игровые аргументы для версий без `arguments.game`
+fn default_game_arguments() -> Vec<Argument> {
+    plain(&[
+        "--username", "${auth_player_name}",
+        "--version", "${version_name}",
+        "--gameDir", "${game_directory}",
+        "--assetsDir", "${assets_root}",
+        "--assetIndex", "${assets_index_name}",
+        "--uuid", "${auth_uuid}",
+        "--accessToken", "${auth_access_token}",
+        "--userType", "${user_type}",
+    ])
+}
+
+/// Размер окна для версий без `arguments.game`
+fn legacy_resolution_arguments() -> Vec<Argument> {
+    vec![with_feature(
+        "has_custom_resolution",
+        &["--width", "${resolution_width}", "--height", "${resolution_height}"],
+    )]
+}
+
+/// Аргументы, которые добавляет сам лаунчер поверх version.json
+fn launcher_game_arguments() -> Vec<Argument> {
+    vec![with_feature("is_fullscreen", &["--fullscreen"])]
+}
+
+/// Подставляет значения в `${name}`. Неизвестные плейсхолдеры остаются как есть.
+pub fn expand_template(template: &str, vars: &ArgumentVars) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+                match vars.get(name) {
+                    Some(value) => result.push_str(value),
+                    None => result.push_str(&rest[start..start + 2 + end + 1]),
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Разворачивает список аргументов: строки и условные элементы `{ rules, value }`
+pub fn resolve_arguments(
+    list: &[Argument],
+    platform: &Platform,
+    features: &Features,
+    vars: &ArgumentVars,
+) -> Vec<String> {
+    let mut result = Vec::new();
+
+    for entry in list {
+        match entry {
+            Argument::Plain(arg) => result.push(expand_template(arg, vars)),
+            Argument::Conditional { rules, value } => {
+                if rules_allow(Some(rules), platform, features) {
+                    result.extend(value.values().iter().map(|arg| expand_template(arg, vars)));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// JVM аргументы версии (`arguments.jvm` или стандартный набор)
+pub fn jvm_arguments(
+    version_json: &VersionJson,
+    platform: &Platform,
+    features: &Features,
+    vars: &ArgumentVars,
+) -> Vec<String> {
+    let jvm = version_json
+        .arguments
+        .as_ref()
+        .map(|a| a.jvm.as_slice())
+        .unwrap_or_default();
+
+    // Профиль загрузчика может добавить JVM аргументы поверх старой версии без них —
+    // тогда classpath и natives всё равно нужно передать стандартным набором
+    let has_classpath = jvm.iter().any(|arg| match arg {
+        Argument::Plain(value) => value.contains("${classpath}"),
+        Argument::Conditional { value, .. } => {
+            value.values().iter().any(|v| v.contains("${classpath}"))
+        }
+    });
+
+    let mut args = Vec::new();
+    if !has_classpath {
+        args.extend(resolve_arguments(&default_jvm_arguments(), platform, features, vars));
+    }
+    args.extend(resolve_arguments(jvm, platform, features, vars));
+    args
+}
+
+/// Игровые аргументы версии плюс аргументы лаунчера. Источник по приоритету:
+/// `arguments.game`, строка `minecraftArguments` старых версий, стандартный набор.
+pub fn game_arguments(
+    version_json: &VersionJson,
+    platform: &Platform,
+    features: &Features,
+    vars: &ArgumentVars,
+) -> Vec<String> {
+    let mut args = match version_json.arguments.as_ref().filter(|a| !a.game.is_empty()) {
+        Some(arguments) => resolve_arguments(&arguments.game, platform, features, vars),
+        None => {
+            let mut args = match &version_json.minecraft_arguments {
+                Some(template) => template
+                    .split_whitespace()
+                    .map(|arg| expand_template(arg, vars))
+                    .collect(),
+                None => resolve_arguments(&default_game_arguments(), platform, features, vars),
+            };
+            // Здесь нет условных элементов — размер окна добавляем сами
+            args.extend(resolve_arguments(&legacy_resolution_arguments(), platform, features, vars));
+            args
+        }
+    };
+    args.extend(resolve_arguments(&launcher_game_arguments(), platform, features, vars));
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn linux() -> Platform {
+        Platform::new("linux", "x86_64", "")
+    }
+
+    fn vars() -> ArgumentVars {
+        HashMap::from([
+            ("auth_player_name", "Steve".to_string()),
+            ("version_name", "1.21.4".to_string()),
+            ("game_directory", "/games/run".to_string()),
+            ("resolution_width", "1280".to_string()),
+            ("resolution_height", "720".to_string()),
+            ("quickPlaySingleplayer", "World".to_string()),
+            ("natives_directory", "/natives".to_string()),
+            ("classpath", "a.jar:b.jar".to_string()),
+        ])
+    }
+
+    fn version(value: serde_json::Value) -> VersionJson {
+        serde_json::from_value(value).expect("valid version JSON")
+    }
+
+    fn features(names: &[&str]) -> Features {
+        let mut features = Features::default();
+        for name in names {
+            features.enable(name);
+        }
+        features
+    }
+
+    /// `arguments.game` из 1.21.4 (сокращённо)
+    fn modern_version() -> VersionJson {
+        version(json!({
+            "id": "1.21.4",
+            "arguments": {
+                "game": [
+                    "--username", "${auth_player_name}",
+                    "--version", "${version_name}",
+                    {
+                        "rules": [{ "action": "allow", "features": { "has_custom_resolution": true } }],
+                        "value": ["--width", "${resolution_width}", "--height", "${resolution_height}"]
+                    },
+                    {
+                        "rules": [{ "action": "allow", "features": { "has_quick_plays_support": true } }],
+                        "value": ["--quickPlayPath", "${quickPlayPath}"]
+                    },
+                    {
+                        "rules": [{ "action": "allow", "features": { "is_quick_play_singleplayer": true } }],
+                        "value": ["--quickPlaySingleplayer", "${quickPlaySingleplayer}"]
+                    },
+                    {
+                        "rules": [{ "action": "allow", "features": { "is_quick_play_multiplayer": true } }],
+                        "value": ["--quickPlayMultiplayer", "${quickPlayMultiplayer}"]
+                    }
+                ],
+                "jvm": [
+                    {
+                        "rules": [{ "action": "allow", "os": { "name": "osx" } }],
+                        "value": ["-XstartOnFirstThread"]
+                    },
+                    "-Djava.library.path=${natives_directory}",
+                    "-cp", "${classpath}"
+                ]
+            }
+        }))
+    }
+
+    #[test]
+    fn expands_known_placeholders() {
+        assert_eq!(
+            expand_template("--gameDir=${game_directory}/${version_name}", &vars()),
+            "--gameDir=/games/run/1.21.4"
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_and_unterminated_placeholders_alone() {
+        assert_eq!(expand_template("${clientid}", &vars()), "${clientid}");
+        assert_eq!(
+            expand_template("${auth_player_name}-${user_properties}", &vars()),
+            "Steve-${user_properties}"
+        );
+        assert_eq!(expand_template("prefix ${version_name", &vars()), "prefix ${version_name");
+        assert_eq!(expand_template("no placeholders", &vars()), "no placeholders");
+    }
+
+    #[test]
+    fn feature_gated_arguments_need_their_feature() {
+        let json = modern_version();
+
+        let args = game_arguments(&json, &linux(), &Features::default(), &vars());
+        assert_eq!(args, ["--username", "Steve", "--version", "1.21.4"]);
+
+        let args = game_arguments(
+            &json,
+            &linux(),
+            &features(&["has_custom_resolution", "is_quick_play_singleplayer", "is_fullscreen"]),
+            &vars(),
+        );
+        assert_eq!(
+            args,
+            [
+                "--username", "Steve",
+                "--version", "1.21.4",
+                "--width", "1280", "--height", "720",
+                "--quickPlaySingleplayer", "World",
+                "--fullscreen",
+            ]
+        );
+    }
+
+    #[test]
+    fn os_rules_filter_jvm_arguments() {
+        let json = modern_version();
+        let osx = Platform::new("osx", "arm64", "");
+
+        let args = jvm_arguments(&json, &osx, &Features::default(), &vars());
+        assert_eq!(args[0], "-XstartOnFirstThread");
+
+        let args = jvm_arguments(&json, &linux(), &Features::default(), &vars());
+        assert_eq!(args, ["-Djava.library.path=/natives", "-cp", "a.jar:b.jar"]);
+    }
+
+    #[test]
+    fn jvm_arguments_without_classpath_get_the_default_set() {
+        let json = version(json!({ "id": "fabric", "arguments": { "jvm": ["-DFabricMcEmu=x"] } }));
+        let args = jvm_arguments(&json, &linux(), &Features::default(), &vars());
+        assert_eq!(
+            args,
+            ["-Djava.library.path=/natives", "-cp", "a.jar:b.jar", "-DFabricMcEmu=x"]
+        );
+    }
+
+    #[test]
+    fn falls_back_to_minecraft_arguments() {
+        let json = version(json!({
+            "id": "1.8.9",
+            "minecraftArguments": "--username ${auth_player_name} --version ${version_name}"
+        }));
+        let args = game_arguments(&json, &linux(), &features(&["has_custom_resolution"]), &vars());
+        assert_eq!(
+            args,
+            ["--username", "Steve", "--version", "1.21.4", "--width", "1280", "--height", "720"]
+        );
+    }
+
+    #[test]
+    fn falls_back_to_default_game_arguments() {
+        // Пустой `arguments.game` не считается — берётся стандартный набор
+        let json = version(json!({ "id": "old", "arguments": { "game": [] } }));
+        let args = game_arguments(&json, &linux(), &Features::default(), &vars());
+        assert_eq!(&args[..4], ["--username", "Steve", "--version", "1.21.4"]);
+        assert!(args.contains(&"--accessToken".to_string()));
+        assert!(!args.contains(&"--width".to_string()));
+    }
+}
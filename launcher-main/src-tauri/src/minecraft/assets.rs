@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::model::{read_json_file, AssetIndex};
+
+/// Готовит ассеты для старых версий и возвращает каталог для `${game_assets}`.
+///
+/// Современные индексы читаются игрой напрямую из `assets/objects`.
+/// Индекс `legacy` (`virtual: true`) раскладывается по именам в `assets/virtual/<id>`,
+/// а `pre-1.6` (`map_to_resources: true`) — в `<game_dir>/resources`.
+pub fn prepare_assets(
+    assets_dir: &Path,
+    game_dir: &Path,
+    index_id: &str,
+) -> anyhow::Result<PathBuf> {
+    let index_path = assets_dir.join("indexes").join(format!("{index_id}.json"));
+    if !index_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Asset index {} not found. Reinstall the game files.",
+            index_id
+        ));
+    }
+    let index: AssetIndex = read_json_file(&index_path)?;
+
+    let objects_dir = assets_dir.join("objects");
+    let mut game_assets = assets_dir.to_path_buf();
+
+    if index.is_virtual {
+        game_assets = assets_dir.join("virtual").join(index_id);
+        materialize(&index, &objects_dir, &game_assets)?;
+    }
+
+    if index.map_to_resources {
+        materialize(&index, &objects_dir, &game_dir.join("resources"))?;
+    }
+
+    Ok(game_assets)
+}
+
+/// Копирует объекты из хеш-хранилища под их исходными именами
+fn materialize(index: &AssetIndex, objects_dir: &Path, target_dir: &Path) -> anyhow::Result<()> {
+    for (name, object) in &index.objects {
+        let object_path = match object.object_path() {
+            Some(path) => path,
+            None => continue,
+        };
+
+        let target = target_dir.join(name);
+        let up_to_date = fs::metadata(&target)
+            .map(|m| m.len() == object.size)
+            .unwrap_or(false);
+        if up_to_date {
+            continue;
+        }
+
+        let source = objects_dir.join(object_path);
+        if !source.exists() {
+            return Err(anyhow::anyhow!(
+                "Asset object {} ({}) is missing. Reinstall the game files.",
+                name,
+                object.hash
+            ));
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&source, &target)?;
+    }
+
+    Ok(())
+}
@@ -1,101 +1,60 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use serde_json::Value;
+use super::model::{Library, VersionJson};
+use super::rules::{rules_allow, Features, Platform};
 
 pub(crate) fn build_classpath(
-    launch_dir: &PathBuf,
-    version_json: &Value,
-    version_jar_path: &PathBuf,
+    launch_dir: &Path,
+    version_json: &VersionJson,
+    version_jar_path: &Path,
+    platform: &Platform,
 ) -> anyhow::Result<Vec<String>> {
     let mut entries: Vec<String> = Vec::new();
+    let mut missing: Vec<String> = Vec::new();
     let libraries_dir = launch_dir.join("libraries");
 
-    if let Some(libs) = version_json.get("libraries").and_then(|v| v.as_array()) {
-        for lib in libs {
-            if !is_library_allowed_for_current_os(lib) {
-                continue;
-            }
-
-            let name = match lib.get("name").and_then(|v| v.as_str()) {
-                Some(n) => n,
-                None => continue,
-            };
+    for lib in &version_json.libraries {
+        if !is_library_allowed(lib, platform) {
+            continue;
+        }
 
-            if name.contains(":natives-") {
-                continue;
-            }
+        // Старые нативы (`natives`) не идут в classpath — они распаковываются в natives/.
+        // Современные `natives-<os>` jar'ы LWJGL 3 грузит прямо из classpath.
+        if lib.is_natives_only() {
+            continue;
+        }
 
-            if let Some(path) = maven_name_to_relative_jar_path(name) {
-                let full_path = libraries_dir.join(path);
-                if full_path.exists() {
-                    entries.push(full_path.to_string_lossy().to_string());
-                }
+        if let Some(path) = lib.artifact_path() {
+            let full_path = library_file(&libraries_dir, &path);
+            if full_path.exists() {
+                entries.push(full_path.to_string_lossy().to_string());
+            } else {
+                missing.push(lib.name.clone());
             }
         }
     }
 
+    if !missing.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Libraries missing from the classpath: {}",
+            missing.join(", ")
+        ));
+    }
+
     entries.push(version_jar_path.to_string_lossy().to_string());
     Ok(entries)
 }
 
-fn maven_name_to_relative_jar_path(name: &str) -> Option<PathBuf> {
-    let parts: Vec<&str> = name.split(':').collect();
-    if parts.len() < 3 {
-        return None;
-    }
-
-    let group = parts[0];
-    let artifact = parts[1];
-    let version_raw = parts[2];
-    let version = version_raw
-        .split_once('@')
-        .map(|(v, _ext)| v)
-        .unwrap_or(version_raw);
-    let classifier = if parts.len() >= 4 { Some(parts[3]) } else { None };
-
-    let group_path = group.replace('.', &std::path::MAIN_SEPARATOR.to_string());
-
-    let file_name = match classifier {
-        Some(c) => format!("{artifact}-{version}-{c}.jar"),
-        None => format!("{artifact}-{version}.jar"),
-    };
-
-    Some(PathBuf::from(group_path).join(artifact).join(version).join(file_name))
+/// Путь к файлу библиотеки по пути вида `group/artifact/version/file.jar`
+pub(crate) fn library_file(libraries_dir: &Path, relative_path: &str) -> PathBuf {
+    relative_path
+        .split('/')
+        .fold(libraries_dir.to_path_buf(), |dir, part| dir.join(part))
 }
 
-fn is_library_allowed_for_current_os(lib: &Value) -> bool {
-    let rules = match lib.get("rules").and_then(|v| v.as_array()) {
-        Some(r) => r,
-        None => return true,
-    };
-
-    let mut allowed = true;
-    for rule in rules {
-        let action = match rule.get("action").and_then(|v| v.as_str()) {
-            Some(a) => a,
-            None => continue,
-        };
-
-        let os_matches = match rule.get("os") {
-            None => true,
-            Some(os) => {
-                let name = os.get("name").and_then(|v| v.as_str());
-                match name {
-                    None => true,
-                    Some("windows") => cfg!(target_os = "windows"),
-                    Some("linux") => cfg!(target_os = "linux"),
-                    Some("osx") => cfg!(target_os = "macos"),
-                    Some(_) => false,
-                }
-            }
-        };
-
-        if os_matches {
-            allowed = action == "allow";
-        }
-    }
-
-    allowed
+/// Правила библиотек зависят только от платформы — фичи лаунчера в них не используются
+pub(crate) fn is_library_allowed(lib: &Library, platform: &Platform) -> bool {
+    rules_allow(lib.rules.as_deref(), platform, &Features::default())
 }
 
 pub(crate) fn offline_uuid_for_username(username: &str) -> String {
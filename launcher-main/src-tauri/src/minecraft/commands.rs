@@ -1,9 +1,15 @@
 use std::fs;
 
-use tauri::{AppHandle, Manager, Runtime};
+use tauri::{AppHandle, Manager, Runtime, State};
 
-use super::launcher::MinecraftLauncher;
+use super::launcher::{LaunchOutcome, MinecraftLauncher};
+use super::history::LaunchHistory;
+use super::log_history;
+use super::registry::GameRegistry;
 use super::types::{LaunchOptions, WipeResult};
+use super::model::JavaVersion;
+use super::validation;
+use super::version;
 
 pub async fn launch_minecraft<R: Runtime>(
     app: AppHandle<R>,
@@ -13,10 +19,19 @@ pub async fn launch_minecraft<R: Runtime>(
     let launcher = MinecraftLauncher::new_with_path(app_dir, options.install_path.clone());
 
     match launcher.launch(options, app).await {
-        Ok(_) => {
+        Ok(LaunchOutcome::Started { pid }) => {
+            Ok(serde_json::json!({
+            "success": true,
+            "message": "Minecraft launch initiated",
+            "pid": pid
+            }))
+        }
+        Ok(LaunchOutcome::DryRun(command)) => {
             Ok(serde_json::json!({
             "success": true,
-            "message": "Minecraft launch initiated"
+            "message": "Dry run: game not started",
+            "dryRun": true,
+            "command": command
             }))
         }
         Err(e) => {
@@ -170,6 +185,114 @@ pub async fn install_mods<R: Runtime>(
     }
 }
 
+pub async fn install_vanilla_version<R: Runtime>(
+    app: AppHandle<R>,
+    version_id: String,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let app_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let installer = crate::client_installer::ClientInstaller::new_with_path(app_dir, install_path);
+
+    match installer.install_vanilla_version(&app, &version_id).await {
+        Ok(_) => Ok(serde_json::json!({
+            "success": true,
+            "versionId": version_id
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
+pub async fn list_available_versions<R: Runtime>(
+    app: AppHandle<R>,
+    types: Option<Vec<String>>,
+    loader: Option<String>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let app_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let installer = crate::client_installer::ClientInstaller::new_with_path(app_dir, install_path);
+    let filter = crate::client_installer::CatalogFilter {
+        types: types.unwrap_or_default(),
+        loader: loader.filter(|l| !l.is_empty()),
+    };
+
+    match installer.available_versions(&filter).await {
+        Ok(catalog) => Ok(serde_json::json!({
+            "success": true,
+            "catalog": catalog
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
+pub async fn list_installed_versions<R: Runtime>(
+    app: AppHandle<R>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let app_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let launcher = MinecraftLauncher::new_with_path(app_dir, install_path);
+    let versions = version::list_installed_versions(&launcher.get_launch_dir());
+
+    Ok(serde_json::json!({
+        "success": true,
+        "versions": versions
+    }))
+}
+
+pub async fn validate_installation<R: Runtime>(
+    app: AppHandle<R>,
+    version_id: Option<String>,
+    install_path: Option<String>,
+    java_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let app_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let launcher = MinecraftLauncher::new_with_path(app_dir.clone(), install_path.clone());
+    let launch_dir = launcher.get_launch_dir();
+
+    let version_id = match version_id.filter(|id| !id.is_empty()) {
+        Some(id) => id,
+        None => match launcher.find_launch_version_id() {
+            Some(id) => id,
+            None => {
+                return Ok(serde_json::json!({
+                    "success": false,
+                    "error": "No installed version found in launch/versions"
+                }))
+            }
+        },
+    };
+
+    // Та же Java, которую выбрал бы запуск (без установки)
+    let java_path = match java_path.filter(|p| !p.is_empty()) {
+        Some(path) => Some(path),
+        None => match version::load_effective_version_json(&launch_dir, &version_id, None).await {
+            Ok(json) => {
+                let java = json.java_version.unwrap_or_else(JavaVersion::legacy);
+                crate::client_installer::ClientInstaller::new_with_path(app_dir, install_path)
+                    .find_java_runtime(&java)
+                    .await
+            }
+            Err(_) => None,
+        },
+    };
+
+    let java = match java_path {
+        Some(path) => Some(validation::JavaInfo::probe(path).await),
+        None => None,
+    };
+    let report = validation::validate_installation(&launch_dir, &version_id, java).await;
+
+    Ok(serde_json::json!({
+        "success": true,
+        "report": report
+    }))
+}
+
 pub async fn get_client_dirs<R: Runtime>(app: AppHandle<R>) -> Result<serde_json::Value, String> {
     let app_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
     let launcher = MinecraftLauncher::new(app_dir.clone());
@@ -285,7 +408,7 @@ pub async fn check_java_status<R: Runtime>(app: AppHandle<R>) -> Result<serde_js
     };
 
     // Также проверяем системную Java
-    let system_java = crate::client_installer::ClientInstaller::check_system_java();
+    let system_java = crate::client_installer::ClientInstaller::check_system_java().await;
 
     Ok(serde_json::json!({
         "installed": is_installed || system_java.is_some(),
@@ -310,3 +433,162 @@ pub async fn install_java<R: Runtime>(app: AppHandle<R>) -> Result<serde_json::V
         })),
     }
 }
+
+pub async fn list_running_games(state: State<'_, GameRegistry>) -> Result<serde_json::Value, String> {
+    Ok(serde_json::json!({
+        "success": true,
+        "games": state.list()
+    }))
+}
+
+pub async fn kill_game(state: State<'_, GameRegistry>, pid: u32) -> Result<serde_json::Value, String> {
+    match state.kill(pid) {
+        Ok(()) => Ok(serde_json::json!({
+            "success": true,
+            "pid": pid
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
+pub async fn get_game_status(state: State<'_, GameRegistry>, pid: u32) -> Result<serde_json::Value, String> {
+    match state.status(pid) {
+        Some(status) => Ok(serde_json::json!({
+            "success": true,
+            "status": status
+        })),
+        None => Ok(serde_json::json!({
+            "success": false,
+            "error": format!("No game with pid {} was started by the launcher", pid)
+        })),
+    }
+}
+
+/// Папка игры (`run`) для выбранного пути установки
+fn game_dir<R: Runtime>(
+    app: &AppHandle<R>,
+    install_path: Option<String>,
+) -> Result<std::path::PathBuf, String> {
+    let app_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let launcher = MinecraftLauncher::new_with_path(app_dir, install_path);
+    Ok(launcher.get_launch_dir().join("run"))
+}
+
+/// Папка логов игры для выбранного пути установки
+fn game_logs_dir<R: Runtime>(
+    app: &AppHandle<R>,
+    install_path: Option<String>,
+) -> Result<std::path::PathBuf, String> {
+    Ok(game_dir(app, install_path)?.join("logs"))
+}
+
+pub async fn list_game_logs<R: Runtime>(
+    app: AppHandle<R>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let logs_dir = game_logs_dir(&app, install_path)?;
+
+    Ok(serde_json::json!({
+        "success": true,
+        "sessions": log_history::list_sessions(&logs_dir)
+    }))
+}
+
+pub async fn read_game_log<R: Runtime>(
+    app: AppHandle<R>,
+    session: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let logs_dir = game_logs_dir(&app, install_path)?;
+    let session = session.unwrap_or_else(|| log_history::CURRENT_SESSION.to_string());
+
+    match log_history::read_session(
+        &logs_dir,
+        &session,
+        offset.unwrap_or(0),
+        limit.unwrap_or(log_history::DEFAULT_PAGE_SIZE),
+    ) {
+        Ok(page) => Ok(serde_json::json!({
+            "success": true,
+            "page": page
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
+pub async fn search_game_log<R: Runtime>(
+    app: AppHandle<R>,
+    session: Option<String>,
+    query: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let logs_dir = game_logs_dir(&app, install_path)?;
+    let session = session.unwrap_or_else(|| log_history::CURRENT_SESSION.to_string());
+
+    match log_history::search_session(
+        &logs_dir,
+        &session,
+        &query,
+        offset.unwrap_or(0),
+        limit.unwrap_or(log_history::DEFAULT_PAGE_SIZE),
+    ) {
+        Ok(page) => Ok(serde_json::json!({
+            "success": true,
+            "page": page
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
+pub async fn get_total_playtime<R: Runtime>(
+    app: AppHandle<R>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let history = LaunchHistory::load(&game_dir(&app, install_path)?);
+
+    Ok(serde_json::json!({
+        "success": true,
+        "totalPlaytimeMs": history.total_playtime_ms,
+        "totalLaunches": history.total_launches,
+        "totalCrashes": history.total_crashes
+    }))
+}
+
+pub async fn get_last_played<R: Runtime>(
+    app: AppHandle<R>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let history = LaunchHistory::load(&game_dir(&app, install_path)?);
+
+    Ok(serde_json::json!({
+        "success": true,
+        "lastPlayedAt": history.last_played_at,
+        "lastSession": history.sessions.last()
+    }))
+}
+
+pub async fn list_recent_sessions<R: Runtime>(
+    app: AppHandle<R>,
+    limit: Option<usize>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let history = LaunchHistory::load(&game_dir(&app, install_path)?);
+
+    Ok(serde_json::json!({
+        "success": true,
+        "sessions": history.recent(limit.unwrap_or(20))
+    }))
+}
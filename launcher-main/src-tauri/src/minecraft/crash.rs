@@ -0,0 +1,566 @@
+//! Разбор падений игры: краш-репорт, `hs_err_pid*.log` JVM и хвост launcher-java.log
+//! сводятся к одной известной причине с подсказкой, что делать пользователю.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::log_history::CURRENT_LOG;
+
+/// Сколько байт с конца launcher-java.log читать
+const LOG_TAIL_BYTES: usize = 64 * 1024;
+/// Ниже этого объёма памяти OutOfMemoryError лечится увеличением `ram_mb`
+const RECOMMENDED_RAM_MB: u32 = 4096;
+
+/// Библиотеки видеодрайверов, падение в которых — проблема драйвера, а не игры
+const GL_DRIVER_LIBRARIES: [&str; 11] = [
+    "atio6axx",
+    "atioglxx",
+    "atiumd",
+    "ig75icd",
+    "ig8icd",
+    "ig9icd",
+    "igxelpicd",
+    "nvoglv",
+    "libnvidia-glcore",
+    "radeonsi_dri",
+    "iris_dri",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashCause {
+    OutOfMemory,
+    JavaVersion,
+    MissingDependency,
+    MixinFailure,
+    GraphicsDriver,
+    Unknown,
+}
+
+/// Событие `minecraft-crash`
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashAnalysis {
+    pub cause: CrashCause,
+    /// Мод, библиотека или драйвер, из-за которых упала игра
+    pub culprit: Option<String>,
+    pub message: String,
+    /// Что сделать пользователю
+    pub fix: String,
+    pub crash_report: Option<PathBuf>,
+    pub jvm_error_log: Option<PathBuf>,
+}
+
+/// Что известно о запуске, который упал
+pub struct CrashContext<'a> {
+    pub launch_dir: &'a Path,
+    pub game_dir: &'a Path,
+    /// Версия Java, на которой шла игра
+    pub java_major: Option<u32>,
+    pub ram_mb: u32,
+    /// Java из `javaVersion` версии
+    pub required_java: u32,
+    /// Файлы старше запуска к этому падению не относятся
+    pub started_at: SystemTime,
+}
+
+/// Собирает доступные следы падения и определяет причину
+pub fn analyze_crash(ctx: &CrashContext) -> CrashAnalysis {
+    let crash_report = newest_file(
+        &ctx.game_dir.join("crash-reports"),
+        ctx.started_at,
+        |name| name.starts_with("crash-") && name.ends_with(".txt"),
+    );
+    // JVM пишет hs_err в рабочий каталог процесса
+    let jvm_error_log = [ctx.launch_dir, ctx.game_dir]
+        .iter()
+        .filter_map(|dir| {
+            newest_file(dir, ctx.started_at, |name| {
+                name.starts_with("hs_err_pid") && name.ends_with(".log")
+            })
+        })
+        .max_by_key(|path| modified(path));
+
+    let mut text = String::new();
+    for path in crash_report.iter().chain(jvm_error_log.iter()) {
+        if let Ok(content) = fs::read(path) {
+            text.push_str(&String::from_utf8_lossy(&content));
+            text.push('\n');
+        }
+    }
+    text.push_str(&log_tail(&ctx.game_dir.join("logs").join(CURRENT_LOG)));
+
+    let mut analysis = classify(&text, ctx);
+    analysis.crash_report = crash_report;
+    analysis.jvm_error_log = jvm_error_log;
+    analysis
+}
+
+fn classify(text: &str, ctx: &CrashContext) -> CrashAnalysis {
+    java_version_problem(text, ctx)
+        .or_else(|| missing_dependency(text))
+        .or_else(|| mixin_failure(text))
+        .or_else(|| out_of_memory(text, ctx.ram_mb))
+        .or_else(|| graphics_driver(text))
+        .unwrap_or_else(|| unknown(text))
+}
+
+fn java_version_problem(text: &str, ctx: &CrashContext) -> Option<CrashAnalysis> {
+    static TOO_OLD: OnceLock<Regex> = OnceLock::new();
+    static TOO_NEW: OnceLock<Regex> = OnceLock::new();
+    let too_old = TOO_OLD.get_or_init(|| {
+        Regex::new(r"UnsupportedClassVersionError: (\S+) .*?class file version (\d+)")
+            .expect("valid class version pattern")
+    });
+    let too_new = TOO_NEW.get_or_init(|| {
+        Regex::new(r"Unsupported class file major version (\d+)").expect("valid ASM pattern")
+    });
+    let actual = ctx
+        .java_major
+        .map(|major| major.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    if let Some(caps) = too_old.captures(text) {
+        let needed = caps[2].parse::<u32>().unwrap_or(52).saturating_sub(44);
+        return Some(analysis(
+            CrashCause::JavaVersion,
+            Some(caps[1].to_string()),
+            format!("{} needs Java {}, but the game runs on Java {}", &caps[1], needed, actual),
+            "Clear the custom Java path so the launcher picks the right Java, or update the mod for this Java.",
+        ));
+    }
+
+    // Старые версии и моды не понимают классы новых Java
+    let legacy_loader = text.contains("cannot be cast to class java.net.URLClassLoader");
+    if legacy_loader || too_new.is_match(text) {
+        return Some(analysis(
+            CrashCause::JavaVersion,
+            None,
+            format!(
+                "This version needs Java {}, but the game runs on Java {}",
+                ctx.required_java, actual
+            ),
+            "Clear the custom Java path so the launcher picks the right Java.",
+        ));
+    }
+
+    None
+}
+
+fn missing_dependency(text: &str) -> Option<CrashAnalysis> {
+    static MISSING: OnceLock<Regex> = OnceLock::new();
+    static LEGACY: OnceLock<Regex> = OnceLock::new();
+    // Fabric: "- Mod 'Sodium Extra' (sodium-extra) 0.4.18 requires any version of sodium, which is missing!"
+    let missing = MISSING.get_or_init(|| {
+        Regex::new(r"Mod '([^']+)' \(([^)]+)\) \S+ requires (?:any version of |version .*? of )?(.+?), which is missing")
+            .expect("valid dependency pattern")
+    });
+    // Fabric 0.12: "Could not find required mod: sodium-extra requires {sodium @ [*]}"
+    let legacy = LEGACY.get_or_init(|| {
+        Regex::new(r"Could not find required mod: (\S+) requires \{(\S+) @")
+            .expect("valid dependency pattern")
+    });
+
+    let (mod_name, dependency) = match missing.captures(text) {
+        Some(caps) => (caps[1].to_string(), caps[3].to_string()),
+        None => {
+            let caps = legacy.captures(text)?;
+            (caps[1].to_string(), caps[2].to_string())
+        }
+    };
+
+    Some(analysis(
+        CrashCause::MissingDependency,
+        Some(mod_name.clone()),
+        format!(
+            "Mod {} requires {}, which is not installed",
+            mod_name, dependency
+        ),
+        &format!(
+            "Install {} into the mods folder or remove {}.",
+            dependency, mod_name
+        ),
+    ))
+}
+
+fn mixin_failure(text: &str) -> Option<CrashAnalysis> {
+    static WITH_MOD: OnceLock<Regex> = OnceLock::new();
+    static CONFIG: OnceLock<Regex> = OnceLock::new();
+    // "Mixin apply for mod sodium failed sodium.mixins.json:Foo from mod sodium -> net.minecraft..."
+    let with_mod = WITH_MOD.get_or_init(|| {
+        Regex::new(r"Mixin apply for mod (\S+) failed (\S+?):").expect("valid mixin pattern")
+    });
+    let config = CONFIG.get_or_init(|| {
+        Regex::new(r"Mixin (?:apply|prepare) failed (\S+?\.json)|([\w.-]+\.mixins\.json)")
+            .expect("valid mixin pattern")
+    });
+
+    if let Some(caps) = with_mod.captures(text) {
+        return Some(analysis(
+            CrashCause::MixinFailure,
+            Some(caps[1].to_string()),
+            format!("Mod {} failed to apply mixin {}", &caps[1], &caps[2]),
+            &format!(
+                "Update or remove {}; it is not compatible with this game version or other mods.",
+                &caps[1]
+            ),
+        ));
+    }
+
+    let mixin_error = text.contains("MixinApplyError")
+        || text.contains("InvalidInjectionException")
+        || text.contains("MixinTransformerError");
+    if !mixin_error {
+        return None;
+    }
+
+    let culprit = config
+        .captures(text)
+        .and_then(|caps| caps.get(1).or_else(|| caps.get(2)))
+        .map(|m| m.as_str().to_string());
+    Some(analysis(
+        CrashCause::MixinFailure,
+        culprit.clone(),
+        match &culprit {
+            Some(config) => format!("Mixin {} failed to apply", config),
+            None => "A mod failed to apply its mixins".to_string(),
+        },
+        "Update or remove the mod that owns this mixin config.",
+    ))
+}
+
+fn out_of_memory(text: &str, ram_mb: u32) -> Option<CrashAnalysis> {
+    if !text.contains("java.lang.OutOfMemoryError") {
+        return None;
+    }
+
+    let fix = if ram_mb < RECOMMENDED_RAM_MB {
+        format!(
+            "Increase memory in settings to at least {} MB.",
+            RECOMMENDED_RAM_MB
+        )
+    } else {
+        "Lower render distance or remove heavy mods and resource packs.".to_string()
+    };
+    Some(analysis(
+        CrashCause::OutOfMemory,
+        None,
+        format!("The game ran out of memory with {} MB allocated", ram_mb),
+        &fix,
+    ))
+}
+
+fn graphics_driver(text: &str) -> Option<CrashAnalysis> {
+    static FRAME: OnceLock<Regex> = OnceLock::new();
+    // "# C  [atio6axx.dll+0x1a2b3c]" в hs_err
+    let frame = FRAME.get_or_init(|| {
+        Regex::new(r"Problematic frame:\s*\n#\s+C\s+\[([^+\]]+)").expect("valid frame pattern")
+    });
+
+    let driver = frame
+        .captures(text)
+        .map(|caps| caps[1].to_string())
+        .filter(|library| {
+            let library = library.to_lowercase();
+            GL_DRIVER_LIBRARIES
+                .iter()
+                .any(|driver| library.starts_with(driver))
+        });
+    let gl_error = [
+        "GLFW error 65542",
+        "GLFW error 65543",
+        "Pixel format not accelerated",
+        "The driver does not appear to support OpenGL",
+        "No OpenGL context found",
+    ]
+    .iter()
+    .find(|marker| text.contains(*marker));
+
+    let message = match (&driver, gl_error) {
+        (Some(driver), _) => format!("The game crashed inside the graphics driver ({})", driver),
+        (None, Some(error)) => format!("OpenGL could not start: {}", error),
+        (None, None) => return None,
+    };
+    Some(analysis(
+        CrashCause::GraphicsDriver,
+        driver,
+        message,
+        "Update the graphics driver from the GPU vendor's site; on laptops, run the game on the discrete GPU.",
+    ))
+}
+
+fn unknown(text: &str) -> CrashAnalysis {
+    static SUSPECT: OnceLock<Regex> = OnceLock::new();
+    static DESCRIPTION: OnceLock<Regex> = OnceLock::new();
+    let suspect = SUSPECT
+        .get_or_init(|| Regex::new(r"Suspected Mods?: ([^\n,]+)").expect("valid suspect pattern"));
+    let description = DESCRIPTION.get_or_init(|| {
+        Regex::new(r"Description: (.+)\n\s*\n(.+)").expect("valid description pattern")
+    });
+
+    let culprit = suspect
+        .captures(text)
+        .map(|caps| caps[1].trim().to_string())
+        .filter(|name| name != "NONE" && name != "Unknown");
+    let message = description
+        .captures(text)
+        .map(|caps| format!("{}: {}", caps[1].trim(), caps[2].trim()))
+        .unwrap_or_else(|| "The game crashed for an unknown reason".to_string());
+    let fix = match &culprit {
+        Some(name) => format!(
+            "Try removing or updating {}; see the crash report for details.",
+            name
+        ),
+        None => "See the crash report and launcher-java.log for details.".to_string(),
+    };
+
+    analysis(CrashCause::Unknown, culprit, message, &fix)
+}
+
+fn analysis(
+    cause: CrashCause,
+    culprit: Option<String>,
+    message: String,
+    fix: &str,
+) -> CrashAnalysis {
+    CrashAnalysis {
+        cause,
+        culprit,
+        message,
+        fix: fix.to_string(),
+        crash_report: None,
+        jvm_error_log: None,
+    }
+}
+
+/// Самый новый файл каталога, изменённый после `since`
+fn newest_file(dir: &Path, since: SystemTime, matches: impl Fn(&str) -> bool) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| matches(&entry.file_name().to_string_lossy()))
+        .map(|entry| entry.path())
+        .filter(|path| modified(path).is_some_and(|time| time >= since))
+        .max_by_key(|path| modified(path))
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn log_tail(path: &Path) -> String {
+    let content = match fs::read(path) {
+        Ok(content) => content,
+        Err(_) => return String::new(),
+    };
+    let start = content.len().saturating_sub(LOG_TAIL_BYTES);
+    String::from_utf8_lossy(&content[start..]).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify_with(text: &str, java_major: Option<u32>, ram_mb: u32) -> CrashAnalysis {
+        let ctx = CrashContext {
+            launch_dir: Path::new("launch"),
+            game_dir: Path::new("launch/run"),
+            java_major,
+            ram_mb,
+            required_java: 21,
+            started_at: SystemTime::now(),
+        };
+        classify(text, &ctx)
+    }
+
+    #[test]
+    fn class_file_65_needs_java_21() {
+        let log = "\
+Error: LinkageError occurred while loading main class net.fabricmc.loader.impl.launch.knot.KnotClient
+\tjava.lang.UnsupportedClassVersionError: net/fabricmc/loader/impl/launch/knot/KnotClient has been compiled by a more recent version of the Java Runtime (class file version 65.0), this version of the Java Runtime only recognizes class file versions up to 61.0
+";
+        let crash = classify_with(log, Some(17), 4096);
+
+        assert_eq!(crash.cause, CrashCause::JavaVersion);
+        assert_eq!(
+            crash.culprit.as_deref(),
+            Some("net/fabricmc/loader/impl/launch/knot/KnotClient")
+        );
+        assert_eq!(
+            crash.message,
+            "net/fabricmc/loader/impl/launch/knot/KnotClient needs Java 21, but the game runs on Java 17"
+        );
+    }
+
+    #[test]
+    fn asm_rejecting_new_class_files_is_java_version() {
+        let log = "\
+[12:01:44] [main/ERROR]: Unable to launch
+java.lang.IllegalArgumentException: Unsupported class file major version 65
+\tat org.objectweb.asm.ClassReader.<init>(ClassReader.java:199)
+";
+        let crash = classify_with(log, Some(22), 4096);
+
+        assert_eq!(crash.cause, CrashCause::JavaVersion);
+        assert_eq!(
+            crash.message,
+            "This version needs Java 21, but the game runs on Java 22"
+        );
+    }
+
+    #[test]
+    fn fabric_missing_dependency() {
+        let log = "\
+[12:03:10] [main/ERROR]: Incompatible mods found!
+net.fabricmc.loader.impl.FormattedException: Some of your mods are incompatible with the game or each other!
+A potential solution has been determined, this may resolve your problem:
+\t - Install sodium, any version.
+More details:
+\t - Mod 'Sodium Extra' (sodium-extra) 0.6.0+mc1.21.4 requires any version of sodium, which is missing!
+";
+        let crash = classify_with(log, Some(21), 4096);
+
+        assert_eq!(crash.cause, CrashCause::MissingDependency);
+        assert_eq!(crash.culprit.as_deref(), Some("Sodium Extra"));
+        assert_eq!(
+            crash.message,
+            "Mod Sodium Extra requires sodium, which is not installed"
+        );
+    }
+
+    #[test]
+    fn legacy_fabric_missing_dependency() {
+        let log = "\
+net.fabricmc.loader.discovery.ModResolutionException: Could not find required mod: sodium-extra requires {sodium @ [>=0.2.0]}
+";
+        let crash = classify_with(log, Some(17), 4096);
+
+        assert_eq!(crash.cause, CrashCause::MissingDependency);
+        assert_eq!(crash.culprit.as_deref(), Some("sodium-extra"));
+        assert_eq!(
+            crash.message,
+            "Mod sodium-extra requires sodium, which is not installed"
+        );
+    }
+
+    #[test]
+    fn mixin_apply_failure_names_the_mod() {
+        let log = "\
+[12:05:31] [Render thread/ERROR]: Mixin apply for mod iris failed iris.mixins.json:MixinGameRenderer from mod iris -> net.minecraft.class_757: org.spongepowered.asm.mixin.injection.throwables.InvalidInjectionException Critical injection failure
+";
+        let crash = classify_with(log, Some(21), 4096);
+
+        assert_eq!(crash.cause, CrashCause::MixinFailure);
+        assert_eq!(crash.culprit.as_deref(), Some("iris"));
+        assert_eq!(
+            crash.message,
+            "Mod iris failed to apply mixin iris.mixins.json"
+        );
+    }
+
+    #[test]
+    fn mixin_transformer_error_names_the_config() {
+        let log = "\
+java.lang.RuntimeException: org.spongepowered.asm.mixin.transformer.throwables.MixinTransformerError: An unexpected critical error was encountered
+Caused by: org.spongepowered.asm.mixin.throwables.MixinApplyError: Mixin [sodium.mixins.json:core.MinecraftClientMixin] from phase [DEFAULT] in config [sodium.mixins.json] FAILED during APPLY
+";
+        let crash = classify_with(log, Some(21), 4096);
+
+        assert_eq!(crash.cause, CrashCause::MixinFailure);
+        assert_eq!(crash.culprit.as_deref(), Some("sodium.mixins.json"));
+    }
+
+    #[test]
+    fn out_of_memory_suggests_more_ram_below_recommended() {
+        let log = "\
+[12:10:02] [Server thread/ERROR]: Encountered an unexpected exception
+java.lang.OutOfMemoryError: Java heap space
+";
+        let crash = classify_with(log, Some(21), 2048);
+
+        assert_eq!(crash.cause, CrashCause::OutOfMemory);
+        assert_eq!(
+            crash.message,
+            "The game ran out of memory with 2048 MB allocated"
+        );
+        assert!(crash.fix.contains("4096 MB"));
+
+        let crash = classify_with(log, Some(21), 8192);
+        assert!(!crash.fix.contains("Increase memory"));
+    }
+
+    #[test]
+    fn crash_inside_gl_driver() {
+        let hs_err = "\
+#
+# A fatal error has been detected by the Java Runtime Environment:
+#
+#  EXCEPTION_ACCESS_VIOLATION (0xc0000005) at pc=0x00007ffb1c2d3e4f, pid=12345, tid=6789
+#
+# JRE version: OpenJDK Runtime Environment Temurin-21.0.3+9 (21.0.3+9) (build 21.0.3+9-LTS)
+# Java VM: OpenJDK 64-Bit Server VM Temurin-21.0.3+9 (21.0.3+9-LTS, mixed mode, sharing, tiered, compressed oops, compressed class ptrs, g1 gc, windows-amd64)
+# Problematic frame:
+# C  [atio6axx.dll+0x1a3e4f]
+";
+        let crash = classify_with(hs_err, Some(21), 4096);
+
+        assert_eq!(crash.cause, CrashCause::GraphicsDriver);
+        assert_eq!(crash.culprit.as_deref(), Some("atio6axx.dll"));
+    }
+
+    #[test]
+    fn glfw_without_opengl_is_graphics_driver() {
+        let log = "\
+[12:00:05] [Render thread/ERROR]: GLFW error during init: [0x10007]65543
+[12:00:05] [Render thread/ERROR]: GLFW error 65542: WGL: The driver does not appear to support OpenGL.
+";
+        let crash = classify_with(log, Some(21), 4096);
+
+        assert_eq!(crash.cause, CrashCause::GraphicsDriver);
+        assert_eq!(crash.culprit, None);
+        assert_eq!(crash.message, "OpenGL could not start: GLFW error 65542");
+    }
+
+    #[test]
+    fn unknown_crash_uses_report_description() {
+        let report = "\
+---- Minecraft Crash Report ----
+// Who set us up the TNT?
+
+Time: 2024-12-05 18:22:01
+Description: Ticking entity
+
+java.lang.NullPointerException: Cannot invoke \"net.minecraft.class_1297.method_5864()\" because \"entity\" is null
+\tat net.minecraft.class_1937.method_18472(class_1937.java:512)
+
+A detailed walkthrough of the error, its code path and all known details is as follows:
+---------------------------------------------------------------------------------------
+
+-- System Details --
+Details:
+\tMinecraft Version: 1.21.4
+\tSuspected Mods: Create (create)
+";
+        let crash = classify_with(report, Some(21), 4096);
+
+        assert_eq!(crash.cause, CrashCause::Unknown);
+        assert_eq!(crash.culprit.as_deref(), Some("Create (create)"));
+        assert!(crash
+            .message
+            .starts_with("Ticking entity: java.lang.NullPointerException: Cannot invoke"));
+    }
+
+    #[test]
+    fn unknown_crash_without_details() {
+        let crash = classify_with("Suspected Mods: NONE\n", Some(21), 4096);
+
+        assert_eq!(crash.cause, CrashCause::Unknown);
+        assert_eq!(crash.culprit, None);
+        assert_eq!(crash.message, "The game crashed for an unknown reason");
+    }
+}
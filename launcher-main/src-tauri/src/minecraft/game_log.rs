@@ -0,0 +1,318 @@
+//! Построчный разбор вывода игры: обычный текст log4j (`[12:00:00] [Render thread/INFO]: ...`)
+//! и XML-события, которые включает конфиг из `logging.client`.
+
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use regex::Regex;
+use serde::Serialize;
+
+/// Строка лога игры для события `minecraft-log`
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GameLogLine {
+    pub message: String,
+    /// Время события, мс с начала эпохи
+    pub timestamp: u64,
+    /// stdout или stderr
+    pub stream: &'static str,
+    pub level: Option<String>,
+    pub thread: Option<String>,
+    pub logger: Option<String>,
+}
+
+impl GameLogLine {
+    /// Строка для launcher-java.log
+    pub fn to_log_line(&self) -> String {
+        match (&self.thread, &self.level) {
+            (Some(thread), Some(level)) => format!("[{}/{}]: {}", thread, level, self.message),
+            (None, Some(level)) => format!("[{}]: {}", level, self.message),
+            _ => self.message.clone(),
+        }
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn plain_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^\[[^\]]*\] \[(?P<thread>[^\]]+)/(?P<level>[A-Z]+)\](?: \[[^\]]*\])?: (?P<message>.*)$")
+            .expect("valid log pattern")
+    })
+}
+
+fn attribute_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r#"(\w+)="([^"]*)""#).expect("valid attribute pattern"))
+}
+
+/// Разбирает поток строк одного stdout/stderr. XML-событие может занимать несколько строк,
+/// поэтому парсер копит их до `</log4j:Event>`.
+pub struct LogParser {
+    stream: &'static str,
+    xml: bool,
+    pending: Option<String>,
+}
+
+impl LogParser {
+    pub fn new(stream: &'static str, xml: bool) -> Self {
+        Self {
+            stream,
+            xml,
+            pending: None,
+        }
+    }
+
+    /// Принимает очередную строку; возвращает готовую запись, если она завершена
+    pub fn push(&mut self, line: &str) -> Option<GameLogLine> {
+        if self.xml {
+            if let Some(pending) = self.pending.as_mut() {
+                pending.push('\n');
+                pending.push_str(line);
+                if line.contains("</log4j:Event>") {
+                    let event = self.pending.take().unwrap_or_default();
+                    return Some(self.parse_xml_event(&event));
+                }
+                return None;
+            }
+
+            if line.trim_start().starts_with("<log4j:Event") {
+                if line.contains("</log4j:Event>") {
+                    return Some(self.parse_xml_event(line));
+                }
+                self.pending = Some(line.to_string());
+                return None;
+            }
+        }
+
+        if line.trim().is_empty() {
+            return None;
+        }
+        Some(self.parse_plain(line))
+    }
+
+    /// Отдаёт незавершённое XML-событие, если поток закончился посреди него
+    pub fn finish(&mut self) -> Option<GameLogLine> {
+        let pending = self.pending.take()?;
+        Some(self.plain_line(pending))
+    }
+
+    fn parse_plain(&self, line: &str) -> GameLogLine {
+        match plain_pattern().captures(line) {
+            Some(caps) => GameLogLine {
+                message: caps["message"].to_string(),
+                timestamp: now_millis(),
+                stream: self.stream,
+                level: Some(caps["level"].to_string()),
+                thread: Some(caps["thread"].to_string()),
+                logger: None,
+            },
+            None => self.plain_line(line.to_string()),
+        }
+    }
+
+    fn parse_xml_event(&self, event: &str) -> GameLogLine {
+        let header_end = event.find('>').unwrap_or(event.len());
+        let mut line = self.plain_line(String::new());
+
+        for caps in attribute_pattern().captures_iter(&event[..header_end]) {
+            let value = unescape_xml(&caps[2]);
+            match &caps[1] {
+                "logger" => line.logger = Some(value),
+                "level" => line.level = Some(value),
+                "thread" => line.thread = Some(value),
+                "timestamp" => {
+                    if let Ok(ts) = value.parse() {
+                        line.timestamp = ts;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut message = cdata_in(event, "log4j:Message").unwrap_or_default();
+        if let Some(throwable) = cdata_in(event, "log4j:Throwable") {
+            message.push('\n');
+            message.push_str(&throwable);
+        }
+        line.message = message;
+        line
+    }
+
+    fn plain_line(&self, message: String) -> GameLogLine {
+        GameLogLine {
+            message,
+            timestamp: now_millis(),
+            stream: self.stream,
+            level: None,
+            thread: None,
+            logger: None,
+        }
+    }
+}
+
+/// Содержимое `<tag>` — CDATA или экранированный текст
+fn cdata_in(event: &str, tag: &str) -> Option<String> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let start = event.find(&open)? + open.len();
+    let end = event[start..].find(&close)? + start;
+    let inner = event[start..end].trim();
+
+    match inner
+        .strip_prefix("<![CDATA[")
+        .and_then(|rest| rest.strip_suffix("]]>"))
+    {
+        Some(cdata) => Some(cdata.to_string()),
+        None => Some(unescape_xml(inner)),
+    }
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Вывод игры с конфигом client-1.12.xml
+    const XML_EVENTS: &str = r#"<log4j:Event logger="net.minecraft.client.Minecraft" timestamp="1734000000000" level="INFO" thread="Render thread">
+  <log4j:Message><![CDATA[Setting user: Steve]]></log4j:Message>
+</log4j:Event>
+
+<log4j:Event logger="net.minecraft.client.Minecraft" timestamp="1734000001234" level="ERROR" thread="Render thread">
+  <log4j:Message><![CDATA[Unreported exception thrown!]]></log4j:Message>
+  <log4j:Throwable><![CDATA[java.lang.NullPointerException: Cannot invoke "net.minecraft.class_310.method_1551()" because "client" is null
+	at net.minecraft.class_310.method_1574(class_310.java:1234)
+	at net.minecraft.class_310.method_1514(class_310.java:567)
+]]></log4j:Throwable>
+</log4j:Event>
+"#;
+
+    fn parse_all(parser: &mut LogParser, text: &str) -> Vec<GameLogLine> {
+        let mut lines: Vec<GameLogLine> =
+            text.lines().filter_map(|line| parser.push(line)).collect();
+        lines.extend(parser.finish());
+        lines
+    }
+
+    #[test]
+    fn parses_plain_console_lines() {
+        let mut parser = LogParser::new("stdout", false);
+
+        let line = parser
+            .push("[12:34:56] [Render thread/INFO]: Setting user: Steve")
+            .unwrap();
+        assert_eq!(line.thread.as_deref(), Some("Render thread"));
+        assert_eq!(line.level.as_deref(), Some("INFO"));
+        assert_eq!(line.message, "Setting user: Steve");
+        assert_eq!(line.stream, "stdout");
+
+        // Fabric добавляет имя логгера третьим блоком
+        let line = parser
+            .push("[12:34:50] [main/INFO] [FabricLoader/GameProvider]: Loading Minecraft 1.21.4 with Fabric Loader 0.16.10")
+            .unwrap();
+        assert_eq!(line.thread.as_deref(), Some("main"));
+        assert_eq!(
+            line.message,
+            "Loading Minecraft 1.21.4 with Fabric Loader 0.16.10"
+        );
+    }
+
+    #[test]
+    fn keeps_unformatted_lines_as_is_and_skips_blank_ones() {
+        let mut parser = LogParser::new("stderr", false);
+
+        let line = parser
+            .push("\tat net.minecraft.client.main.Main.main(Main.java:239)")
+            .unwrap();
+        assert_eq!(
+            line.message,
+            "\tat net.minecraft.client.main.Main.main(Main.java:239)"
+        );
+        assert!(line.level.is_none());
+        assert!(line.thread.is_none());
+
+        assert!(parser.push("   ").is_none());
+    }
+
+    #[test]
+    fn parses_multi_line_xml_events() {
+        let mut parser = LogParser::new("stdout", true);
+        let lines = parse_all(&mut parser, XML_EVENTS);
+
+        assert_eq!(lines.len(), 2);
+        let first = &lines[0];
+        assert_eq!(first.message, "Setting user: Steve");
+        assert_eq!(first.level.as_deref(), Some("INFO"));
+        assert_eq!(first.thread.as_deref(), Some("Render thread"));
+        assert_eq!(
+            first.logger.as_deref(),
+            Some("net.minecraft.client.Minecraft")
+        );
+        assert_eq!(first.timestamp, 1734000000000);
+    }
+
+    #[test]
+    fn appends_throwable_split_across_lines() {
+        let mut parser = LogParser::new("stdout", true);
+        let lines = parse_all(&mut parser, XML_EVENTS);
+
+        let error = &lines[1];
+        assert_eq!(error.level.as_deref(), Some("ERROR"));
+        let message: Vec<&str> = error.message.lines().collect();
+        assert_eq!(message[0], "Unreported exception thrown!");
+        assert!(message[1].starts_with("java.lang.NullPointerException: Cannot invoke"));
+        assert_eq!(
+            message[2],
+            "\tat net.minecraft.class_310.method_1574(class_310.java:1234)"
+        );
+        assert_eq!(message.len(), 4);
+    }
+
+    #[test]
+    fn unescapes_messages_without_cdata() {
+        let mut parser = LogParser::new("stdout", true);
+        let line = parser
+            .push(r#"<log4j:Event logger="x" timestamp="1" level="WARN" thread="main"><log4j:Message>a &lt; b &amp;&amp; c</log4j:Message></log4j:Event>"#)
+            .unwrap();
+        assert_eq!(line.message, "a < b && c");
+        assert_eq!(line.level.as_deref(), Some("WARN"));
+    }
+
+    #[test]
+    fn plain_lines_between_xml_events_are_parsed_as_text() {
+        let mut parser = LogParser::new("stdout", true);
+        let line = parser
+            .push("[12:00:00] [main/INFO]: Loading Minecraft 1.21.4 with Fabric Loader 0.16.10")
+            .unwrap();
+        assert_eq!(line.level.as_deref(), Some("INFO"));
+        assert!(parser.pending.is_none());
+    }
+
+    #[test]
+    fn unfinished_event_is_flushed_as_raw_text() {
+        let mut parser = LogParser::new("stdout", true);
+        assert!(parser
+            .push(r#"<log4j:Event logger="x" timestamp="1" level="INFO" thread="main">"#)
+            .is_none());
+        assert!(parser.push("  <log4j:Message><![CDATA[cut off").is_none());
+
+        let line = parser.finish().unwrap();
+        assert!(line.message.contains("cut off"));
+        assert!(line.level.is_none());
+        assert!(parser.finish().is_none());
+    }
+}
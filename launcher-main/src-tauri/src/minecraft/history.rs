@@ -0,0 +1,91 @@
+//! История запусков инстанса: время игры, версии и завершения сессий.
+//! Хранится в `run/launch-history.json`; итоги считаются отдельно от списка,
+//! поэтому обрезка старых сессий не сбрасывает общее время игры.
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::crash::CrashCause;
+
+const HISTORY_FILE: &str = "launch-history.json";
+/// Сколько последних сессий хранить подробно
+const MAX_SESSIONS: usize = 200;
+
+/// Одна завершённая сессия
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRecord {
+    /// Время запуска и выхода, мс с начала эпохи
+    pub started_at: u64,
+    pub ended_at: u64,
+    pub duration_ms: u64,
+    pub version_id: String,
+    /// Версия клиента в папке модов на момент запуска
+    pub client_version: Option<String>,
+    pub java_path: String,
+    pub exit_code: Option<i32>,
+    /// Остановлена через `kill_game`
+    pub killed: bool,
+    /// Игра упала (ненулевой код без `kill_game`)
+    pub crashed: bool,
+    /// Игра оставила файл в `crash-reports`
+    pub crash_report: bool,
+    pub crash_cause: Option<CrashCause>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchHistory {
+    pub total_playtime_ms: u64,
+    pub total_launches: u64,
+    pub total_crashes: u64,
+    pub last_played_at: Option<u64>,
+    /// От старых к новым
+    pub sessions: Vec<SessionRecord>,
+}
+
+impl LaunchHistory {
+    /// История инстанса; пустая, если файла нет или он повреждён
+    pub fn load(game_dir: &Path) -> Self {
+        fs::read_to_string(game_dir.join(HISTORY_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, game_dir: &Path) -> anyhow::Result<()> {
+        fs::create_dir_all(game_dir)?;
+        let path = game_dir.join(HISTORY_FILE);
+        let part = path.with_extension("json.part");
+        fs::write(&part, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(&part, &path)?;
+        Ok(())
+    }
+
+    /// Последние `limit` сессий, новые первыми
+    pub fn recent(&self, limit: usize) -> Vec<SessionRecord> {
+        self.sessions.iter().rev().take(limit).cloned().collect()
+    }
+}
+
+/// Добавляет сессию в историю инстанса
+pub fn record_session(game_dir: &Path, session: SessionRecord) -> anyhow::Result<()> {
+    let mut history = LaunchHistory::load(game_dir);
+
+    history.total_playtime_ms += session.duration_ms;
+    history.total_launches += 1;
+    if session.crashed {
+        history.total_crashes += 1;
+    }
+    history.last_played_at = Some(session.ended_at);
+
+    history.sessions.push(session);
+    if history.sessions.len() > MAX_SESSIONS {
+        let excess = history.sessions.len() - MAX_SESSIONS;
+        history.sessions.drain(..excess);
+    }
+
+    history.save(game_dir)
+}
@@ -0,0 +1,233 @@
+//! Пользовательские хуки: команда перед запуском JVM и после выхода игры.
+//! Команда выполняется через системную оболочку в папке игры, данные запуска
+//! передаются переменными окружения `MC_*`, вывод идёт в лог запуска.
+
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Runtime};
+
+use super::log_history::{append_line, SharedLog};
+use super::registry::kill_process_tree;
+use super::types::LogEvent;
+
+/// Таймаут хука, если в настройках он не задан
+pub const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 60;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy)]
+pub enum HookStage {
+    PreLaunch,
+    PostExit,
+}
+
+impl HookStage {
+    fn name(self) -> &'static str {
+        match self {
+            HookStage::PreLaunch => "pre-launch",
+            HookStage::PostExit => "post-exit",
+        }
+    }
+}
+
+/// Что хук узнаёт о запуске
+#[derive(Debug, Clone)]
+pub struct HookContext {
+    pub launch_dir: PathBuf,
+    pub game_dir: PathBuf,
+    pub version_id: String,
+    pub java_path: String,
+}
+
+impl HookContext {
+    fn env(&self, stage: HookStage) -> Vec<(&'static str, String)> {
+        vec![
+            ("MC_HOOK", stage.name().to_string()),
+            (
+                "MC_LAUNCH_DIR",
+                self.launch_dir.to_string_lossy().to_string(),
+            ),
+            ("MC_GAME_DIR", self.game_dir.to_string_lossy().to_string()),
+            ("MC_VERSION_ID", self.version_id.clone()),
+            ("MC_JAVA_PATH", self.java_path.clone()),
+        ]
+    }
+}
+
+/// Выполняет хук и ждёт его не дольше `timeout`.
+/// Ошибка — хук не запустился, не уложился в таймаут или вернул ненулевой код.
+pub fn run_hook<R: Runtime>(
+    app: &AppHandle<R>,
+    stage: HookStage,
+    command: &str,
+    ctx: &HookContext,
+    extra_env: &[(&str, String)],
+    timeout: Duration,
+    log: Option<&SharedLog>,
+) -> anyhow::Result<()> {
+    let prefix = format!("[{} hook]", stage.name());
+    report(app, log, &format!("{} started", prefix));
+
+    let mut cmd = shell_command(command);
+    cmd.current_dir(&ctx.game_dir)
+        .envs(ctx.env(stage))
+        .envs(extra_env.iter().map(|(key, value)| (*key, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("{} hook could not be started: {}", stage.name(), e))?;
+
+    // Потоки чтения не ждём: фоновые процессы хука могут держать вывод открытым
+    if let Some(out) = child.stdout.take() {
+        forward_output(out, prefix.clone(), app.clone(), log.cloned());
+    }
+    if let Some(err) = child.stderr.take() {
+        forward_output(err, prefix.clone(), app.clone(), log.cloned());
+    }
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            // `child.kill` остановил бы только оболочку, а не запущенные ею процессы
+            if let Err(e) = kill_process_tree(child.id()) {
+                log::warn!(
+                    "Failed to kill process tree of {} hook: {}",
+                    stage.name(),
+                    e
+                );
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+            let message = format!(
+                "{} hook timed out after {} s",
+                stage.name(),
+                timeout.as_secs()
+            );
+            report(app, log, &format!("{} {}", prefix, message));
+            return Err(anyhow::anyhow!(message));
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    report(app, log, &format!("{} finished: {}", prefix, status));
+    if !status.success() {
+        return Err(anyhow::anyhow!(
+            "{} hook exited with {}",
+            stage.name(),
+            status
+        ));
+    }
+    Ok(())
+}
+
+fn shell_command(command: &str) -> Command {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").raw_arg(command);
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        cmd
+    }
+
+    #[cfg(not(windows))]
+    {
+        use std::os::unix::process::CommandExt;
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        // Своя группа процессов, чтобы по таймауту остановить всё, что запустил хук
+        cmd.process_group(0);
+        cmd
+    }
+}
+
+fn forward_output<R: Runtime>(
+    source: impl Read + Send + 'static,
+    prefix: String,
+    app: AppHandle<R>,
+    log: Option<SharedLog>,
+) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(source);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let text = String::from_utf8_lossy(&buf);
+                    let line = text.trim_end_matches(['\r', '\n']);
+                    report(&app, log.as_ref(), &format!("{} {}", prefix, line));
+                }
+            }
+        }
+    });
+}
+
+fn report<R: Runtime>(app: &AppHandle<R>, log: Option<&SharedLog>, message: &str) {
+    append_line(log, message);
+    let _ = app.emit(
+        "minecraft-log",
+        LogEvent {
+            message: message.to_string(),
+        },
+    );
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn is_alive(pid: &str) -> bool {
+        Command::new("kill")
+            .args(["-0", pid])
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    #[test]
+    fn timeout_kills_processes_started_by_hook() {
+        let dir = std::env::temp_dir().join(format!("launcher-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let ctx = HookContext {
+            launch_dir: dir.clone(),
+            game_dir: dir.clone(),
+            version_id: "test".to_string(),
+            java_path: "java".to_string(),
+        };
+        let app = tauri::test::mock_app();
+
+        let result = run_hook(
+            app.handle(),
+            HookStage::PreLaunch,
+            "sleep 30 & echo $! > child.pid; wait",
+            &ctx,
+            &[],
+            Duration::from_secs(1),
+            None,
+        );
+
+        assert!(result.unwrap_err().to_string().contains("timed out"));
+        let pid = fs::read_to_string(dir.join("child.pid")).unwrap();
+        // Процесс из группы хука мог ещё не исчезнуть из таблицы процессов
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while is_alive(pid.trim()) && Instant::now() < deadline {
+            thread::sleep(POLL_INTERVAL);
+        }
+        assert!(!is_alive(pid.trim()));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
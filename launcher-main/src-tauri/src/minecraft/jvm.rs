@@ -0,0 +1,324 @@
+//! Настройки JVM из LaunchOptions: пресеты сборщика мусора, пользовательские аргументы
+//! и объём памяти системы для проверки `ram_mb`.
+
+use serde::{Deserialize, Serialize};
+
+/// Память по умолчанию, если `ram_mb` не задан
+pub const DEFAULT_RAM_MB: u32 = 4096;
+
+/// Пресет сборщика мусора
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GcPreset {
+    /// G1 с настройками лаунчера
+    #[default]
+    Default,
+    /// G1 по рекомендациям Aikar
+    #[serde(alias = "aikar_g1")]
+    Aikar,
+    /// Поколенческий ZGC, Java 21+
+    #[serde(alias = "zgc")]
+    ZgcGenerational,
+    /// Shenandoah, Java 12+
+    Shenandoah,
+}
+
+impl GcPreset {
+    /// Минимальная версия Java, в которой есть этот сборщик
+    pub fn min_java(self) -> u32 {
+        match self {
+            GcPreset::Default | GcPreset::Aikar => 8,
+            GcPreset::ZgcGenerational => 21,
+            GcPreset::Shenandoah => 12,
+        }
+    }
+}
+
+/// Флаги сборщика мусора. `java_major` — версия Java, которой будет запущена игра
+/// (None — версию определить не удалось, проверка пропускается).
+pub fn gc_arguments(
+    preset: GcPreset,
+    java_major: Option<u32>,
+    ram_mb: u32,
+) -> anyhow::Result<Vec<String>> {
+    if let Some(major) = java_major {
+        if major < preset.min_java() {
+            return Err(anyhow::anyhow!(
+                "GC preset {:?} needs Java {}+, but the game runs on Java {}",
+                preset,
+                preset.min_java(),
+                major
+            ));
+        }
+    }
+
+    let args: Vec<&str> = match preset {
+        GcPreset::Default => vec![
+            "-XX:+UnlockExperimentalVMOptions",
+            "-XX:+UseG1GC",
+            "-XX:G1NewSizePercent=20",
+            "-XX:G1ReservePercent=20",
+            "-XX:MaxGCPauseMillis=50",
+            "-XX:G1HeapRegionSize=32M",
+        ],
+        GcPreset::Aikar => {
+            // Для больших куч Aikar советует другие размеры поколений
+            let large = ram_mb >= 12 * 1024;
+            let mut args = vec![
+                "-XX:+UseG1GC",
+                "-XX:+ParallelRefProcEnabled",
+                "-XX:MaxGCPauseMillis=200",
+                "-XX:+UnlockExperimentalVMOptions",
+                "-XX:+DisableExplicitGC",
+                "-XX:+AlwaysPreTouch",
+                "-XX:G1HeapWastePercent=5",
+                "-XX:G1MixedGCCountTarget=4",
+                "-XX:G1MixedGCLiveThresholdPercent=90",
+                "-XX:G1RSetUpdatingPauseTimePercent=5",
+                "-XX:SurvivorRatio=32",
+                "-XX:+PerfDisableSharedMem",
+                "-XX:MaxTenuringThreshold=1",
+            ];
+            if large {
+                args.extend([
+                    "-XX:G1NewSizePercent=40",
+                    "-XX:G1MaxNewSizePercent=50",
+                    "-XX:G1HeapRegionSize=16M",
+                    "-XX:G1ReservePercent=15",
+                    "-XX:InitiatingHeapOccupancyPercent=20",
+                ]);
+            } else {
+                args.extend([
+                    "-XX:G1NewSizePercent=30",
+                    "-XX:G1MaxNewSizePercent=40",
+                    "-XX:G1HeapRegionSize=8M",
+                    "-XX:G1ReservePercent=20",
+                    "-XX:InitiatingHeapOccupancyPercent=15",
+                ]);
+            }
+            args
+        }
+        GcPreset::ZgcGenerational => {
+            // С Java 23 ZGC поколенческий по умолчанию, а флаг устарел
+            if java_major.is_some_and(|major| major >= 23) {
+                vec!["-XX:+UseZGC"]
+            } else {
+                vec!["-XX:+UseZGC", "-XX:+ZGenerational"]
+            }
+        }
+        GcPreset::Shenandoah => vec!["-XX:+UseShenandoahGC"],
+    };
+
+    Ok(args.into_iter().map(String::from).collect())
+}
+
+/// Разбирает строку аргументов (JVM опции, команда-обёртка) как shell: пробелы разделяют аргументы,
+/// `'...'` берётся как есть, внутри `"..."` работают `\"` и `\\`.
+/// Обратный слеш вне кавычек экранирует только пробел и кавычки — пути Windows не ломаются.
+pub fn split_args(input: &str) -> anyhow::Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => current.push(ch),
+                        None => return Err(anyhow::anyhow!("Unclosed ' in {}", input)),
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') if matches!(chars.peek(), Some('"') | Some('\\')) => {
+                            current.extend(chars.next());
+                        }
+                        Some(ch) => current.push(ch),
+                        None => return Err(anyhow::anyhow!("Unclosed \" in {}", input)),
+                    }
+                }
+            }
+            '\\' if matches!(chars.peek(), Some(ch) if ch.is_whitespace() || *ch == '"' || *ch == '\'') =>
+            {
+                in_arg = true;
+                current.extend(chars.next());
+            }
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// Память системы в мегабайтах
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemMemory {
+    pub total_mb: u64,
+    /// Сколько можно занять без вытеснения других программ
+    pub available_mb: u64,
+}
+
+/// Объём памяти системы; None, если определить не удалось
+pub fn system_memory() -> Option<SystemMemory> {
+    #[cfg(target_os = "windows")]
+    {
+        // MEMORYSTATUSEX из kernel32
+        #[repr(C)]
+        #[derive(Default)]
+        struct MemoryStatusEx {
+            length: u32,
+            memory_load: u32,
+            total_phys: u64,
+            avail_phys: u64,
+            total_page_file: u64,
+            avail_page_file: u64,
+            total_virtual: u64,
+            avail_virtual: u64,
+            avail_extended_virtual: u64,
+        }
+
+        #[link(name = "kernel32")]
+        extern "system" {
+            fn GlobalMemoryStatusEx(buffer: *mut MemoryStatusEx) -> i32;
+        }
+
+        let mut status = MemoryStatusEx {
+            length: std::mem::size_of::<MemoryStatusEx>() as u32,
+            ..Default::default()
+        };
+        // SAFETY: структура того размера и раскладки, что ждёт WinAPI; length заполнен
+        if unsafe { GlobalMemoryStatusEx(&mut status) } == 0 {
+            return None;
+        }
+        // ullAvailPhys учитывает standby-страницы кеша, в отличие от FreePhysicalMemory из WMI
+        Some(SystemMemory {
+            total_mb: status.total_phys / 1024 / 1024,
+            available_mb: status.avail_phys / 1024 / 1024,
+        })
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let sysctl = |name: &str| -> Option<u64> {
+            let output = std::process::Command::new("sysctl")
+                .args(["-n", name])
+                .output()
+                .ok()?;
+            String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+        };
+        let total = sysctl("hw.memsize")?;
+        let page_size = sysctl("hw.pagesize").unwrap_or(4096);
+
+        // Свободные и неактивные страницы macOS отдаёт по первому требованию
+        let output = std::process::Command::new("vm_stat").output().ok()?;
+        let text = String::from_utf8_lossy(&output.stdout).to_string();
+        let pages = |label: &str| -> u64 {
+            text.lines()
+                .find(|line| line.starts_with(label))
+                .and_then(|line| line.rsplit(' ').next())
+                .and_then(|value| value.trim_end_matches('.').parse().ok())
+                .unwrap_or(0)
+        };
+        let available = (pages("Pages free:") + pages("Pages inactive:")) * page_size;
+
+        Some(SystemMemory {
+            total_mb: total / 1024 / 1024,
+            available_mb: available / 1024 / 1024,
+        })
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+        // Значения в /proc/meminfo — в килобайтах
+        let field = |name: &str| -> Option<u64> {
+            meminfo
+                .lines()
+                .find(|line| line.starts_with(name))?
+                .split_whitespace()
+                .nth(1)?
+                .parse()
+                .ok()
+        };
+
+        Some(SystemMemory {
+            total_mb: field("MemTotal:")? / 1024,
+            available_mb: field("MemAvailable:").or_else(|| field("MemFree:"))? / 1024,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_whitespace() {
+        assert_eq!(
+            split_args("  -Xss2M\t-Dfoo=bar \n -XX:+UseG1GC ").unwrap(),
+            vec!["-Xss2M", "-Dfoo=bar", "-XX:+UseG1GC"]
+        );
+        assert!(split_args("").unwrap().is_empty());
+        assert!(split_args("   ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn quotes_keep_spaces() {
+        assert_eq!(
+            split_args(r#"-Dpath="C:\Program Files\Java" 'it''s' "a b"c"#).unwrap(),
+            vec![r"-Dpath=C:\Program Files\Java", "its", "a bc"]
+        );
+        // Внутри '...' обратный слеш и двойные кавычки не особые
+        assert_eq!(
+            split_args(r#"'say "hi" \'"#).unwrap(),
+            vec![r#"say "hi" \"#]
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(
+            split_args(r#""quote \" and \\ slash" \"bare\""#).unwrap(),
+            vec![r#"quote " and \ slash"#, r#""bare""#]
+        );
+        assert_eq!(split_args(r"my\ file.jar").unwrap(), vec!["my file.jar"]);
+        // Пути Windows вне кавычек не теряют слеши
+        assert_eq!(
+            split_args(r"C:\Users\Steve\java.exe -jar").unwrap(),
+            vec![r"C:\Users\Steve\java.exe", "-jar"]
+        );
+    }
+
+    #[test]
+    fn empty_quoted_strings_are_arguments() {
+        assert_eq!(split_args(r#"a "" '' b"#).unwrap(), vec!["a", "", "", "b"]);
+    }
+
+    #[test]
+    fn unterminated_quotes_are_errors() {
+        assert!(split_args(r#"-Dfoo="bar"#).is_err());
+        assert!(split_args("'unclosed").is_err());
+        assert!(split_args(r#""escaped at end\""#).is_err());
+    }
+}
@@ -1,17 +1,35 @@
 use std::fs;
 use std::path::PathBuf;
 
-use tauri::{AppHandle, Emitter, Runtime};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 
+use super::assets::prepare_assets;
+use super::hooks::{run_hook, HookContext, HookStage};
+use super::jvm::DEFAULT_RAM_MB;
+use super::model::JavaVersion;
+use super::natives::extract_natives;
 use super::options::fix_gui_scale;
 use super::process::{
     build_java_command, emit_launch_progress, emit_launch_success, emit_pre_launch,
-    spawn_and_monitor, JavaLaunchParams,
+    hook_timeout, open_session_log, spawn_and_monitor, JavaLaunchParams, LaunchCommand,
 };
+use super::registry::GameRegistry;
+use super::rules::Platform;
 use super::types::{LaunchOptions, LogEvent, ProgressEvent};
-use super::version::{find_installed_version_id, load_effective_version_json};
+use super::validation::{check_memory, validate_installation, JavaInfo};
+use super::version::{
+    find_installed_version_id, list_installed_versions, load_effective_version_json,
+    resolve_version_jar,
+};
 use crate::client_installer::ClientInstaller;
 
+/// Чем закончился `launch`
+pub enum LaunchOutcome {
+    Started { pid: u32 },
+    /// Команда собрана, но игра не запускалась (`dry_run`)
+    DryRun(LaunchCommand),
+}
+
 pub struct MinecraftLauncher {
     base_dir: PathBuf,
     launch_dir: PathBuf,
@@ -52,11 +70,17 @@ impl MinecraftLauncher {
         self.launch_dir.clone()
     }
 
+    /// Версия для запуска: профиль лаунчера по умолчанию, иначе любая установленная
+    pub(crate) fn find_launch_version_id(&self) -> Option<String> {
+        ClientInstaller::installed_default_version_id(&self.launch_dir)
+            .or_else(|| find_installed_version_id(&self.launch_dir))
+    }
+
     pub async fn launch<R: Runtime>(
         &self,
         options: LaunchOptions,
         app: AppHandle<R>,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<LaunchOutcome> {
         self.ensure_directories()?;
 
         app.emit(
@@ -77,21 +101,52 @@ impl MinecraftLauncher {
         let assets_dir = self.launch_dir.join("assets");
         let game_dir = self.launch_dir.join("run");
 
-        let mut version_id = find_installed_version_id(&self.launch_dir);
+        // Второй процесс в той же папке ломает миры и options.txt. Папка занимается сразу,
+        // чтобы два одновременных запуска не прошли проверку оба
+        let reservation = app
+            .state::<GameRegistry>()
+            .reserve(&game_dir.to_string_lossy())?;
+
+        // Явно выбранная версия должна быть установлена — чужие версии не подставляем
+        let mut version_id = match options.version_id.as_deref().filter(|id| !id.is_empty()) {
+            Some(requested) => {
+                let installed = list_installed_versions(&self.launch_dir)
+                    .into_iter()
+                    .find(|v| v.id == requested);
+                match installed {
+                    Some(v) if v.is_complete() => Some(v.id),
+                    Some(_) => {
+                        return Err(anyhow::anyhow!(
+                            "Version {} is not fully installed (missing JSON or game jar)",
+                            requested
+                        ))
+                    }
+                    None => {
+                        return Err(anyhow::anyhow!(
+                            "Version {} is not installed in {}",
+                            requested,
+                            self.launch_dir.join("versions").display()
+                        ))
+                    }
+                }
+            }
+            None => self.find_launch_version_id(),
+        };
+
+        let installer = ClientInstaller::new_with_path(self.base_dir.clone(), options.install_path.clone());
 
         // Установка клиента если нужно
         if version_id.is_none() || !assets_dir.exists() {
             app.emit(
                 "minecraft-log",
                 LogEvent {
-                    message: "Launch runtime not found. Downloading launch.zip...".into(),
+                    message: "Launch runtime not found. Installing game files...".into(),
                 },
             )?;
 
-            let installer = ClientInstaller::new_with_path(self.base_dir.clone(), options.install_path.clone());
             installer.install_launch_files(&app).await?;
 
-            version_id = find_installed_version_id(&self.launch_dir);
+            version_id = version_id.or_else(|| self.find_launch_version_id());
 
             if version_id.is_none() || !assets_dir.exists() {
                 let error_msg =
@@ -106,7 +161,8 @@ impl MinecraftLauncher {
             .ok_or_else(|| anyhow::anyhow!("No installed version found in launch/versions"))?;
 
         let version_dir = self.launch_dir.join("versions").join(&version_id);
-        let version_jar_path = version_dir.join(format!("{version_id}.jar"));
+        let version_jar_path = resolve_version_jar(&self.launch_dir, &version_id)
+            .ok_or_else(|| anyhow::anyhow!("Game jar not found for version {}", version_id))?;
         let natives_dir = version_dir.join("natives");
 
         // Исправляем GUI scale в options.txt перед запуском
@@ -114,34 +170,49 @@ impl MinecraftLauncher {
 
         fs::create_dir_all(&game_dir)?;
 
-        let effective_version_json = load_effective_version_json(&self.launch_dir, &version_id)?;
+        let manifest = installer.manifest_client();
+        let effective_version_json =
+            load_effective_version_json(&self.launch_dir, &version_id, Some(&manifest)).await?;
+
+        // Докачиваем библиотеки, которых нет на диске: без них игра падает с ClassNotFound
+        installer.install_libraries(&app, &effective_version_json).await?;
+        // Без конфига логирования игра пишет обычный текст — запуск не прерываем
+        if let Err(e) = installer.install_log_config(&effective_version_json).await {
+            log::warn!("Failed to download logging config: {}", e);
+        }
+
+        // Java под версию игры: javaVersion из version.json, для старых версий — Java 8
+        let java_version = effective_version_json
+            .java_version
+            .clone()
+            .unwrap_or_else(JavaVersion::legacy);
 
-        // Проверяем/устанавливаем Java 21
-        let installer = ClientInstaller::new_with_path(self.base_dir.clone(), options.install_path.clone());
-        
         app.emit(
             "minecraft-log",
             LogEvent {
-                message: "Проверка Java 21...".into(),
+                message: format!("Проверка Java {}...", java_version.major_version),
             },
         )?;
 
         let java_cmd = match options.java_path.clone() {
             Some(path) if !path.is_empty() => path,
             _ => {
-                // Автоматически устанавливаем Java 21 если нужно
-                match installer.ensure_java_installed(&app).await {
+                // Автоматически устанавливаем нужную Java если её нет
+                match installer.ensure_java_runtime(&app, &java_version).await {
                     Ok(java_path) => {
                         app.emit(
                             "minecraft-log",
                             LogEvent {
-                                message: format!("Java 21 готова: {}", java_path),
+                                message: format!("Java {} готова: {}", java_version.major_version, java_path),
                             },
                         )?;
                         java_path
                     }
                     Err(e) => {
-                        let error_msg = format!("Ошибка установки Java 21: {}. Попробуйте установить Java вручную.", e);
+                        let error_msg = format!(
+                            "Ошибка установки Java {}: {}. Попробуйте установить Java вручную.",
+                            java_version.major_version, e
+                        );
                         app.emit("minecraft-log", LogEvent { message: error_msg.clone() })?;
                         return Err(anyhow::anyhow!(error_msg));
                     }
@@ -151,6 +222,59 @@ impl MinecraftLauncher {
 
         emit_launch_progress(&app, &java_cmd)?;
 
+        // Версию узнаём один раз: её ждут пресет GC, проверка установки и разбор падения
+        let java = JavaInfo::probe(java_cmd.clone()).await;
+        let java_major = java.major;
+
+        // Проверяем установку до распаковки нативов и сборки команды: они падают на первом
+        // же недостающем файле, а отчёт перечисляет все проблемы с подсказками
+        let mut report = validate_installation(&self.launch_dir, &version_id, Some(java)).await;
+        check_memory(options.ram_mb.unwrap_or(DEFAULT_RAM_MB), &mut report);
+        app.emit("minecraft-validation", &report)?;
+        for replaced in &report.library_overrides {
+            app.emit(
+                "minecraft-log",
+                LogEvent {
+                    message: format!(
+                        "Library {} from {} is overridden by {} from {}",
+                        replaced.parent_library,
+                        replaced.parent_version,
+                        replaced.child_library,
+                        replaced.child_version
+                    ),
+                },
+            )?;
+        }
+        for issue in &report.issues {
+            app.emit(
+                "minecraft-log",
+                LogEvent {
+                    message: format!("[{:?}] {} — {}", issue.severity, issue.message, issue.fix),
+                },
+            )?;
+        }
+        if report.has_errors() {
+            return Err(anyhow::anyhow!(
+                "Installation check failed:\n{}",
+                report.summary()
+            ));
+        }
+
+        // LWJGL 2 и старые LWJGL 3 грузят нативы из -Djava.library.path
+        extract_natives(
+            &self.launch_dir,
+            &effective_version_json,
+            &natives_dir,
+            Platform::current(),
+        )?;
+
+        // Старые версии ждут ассеты по именам (virtual/legacy или run/resources)
+        let game_assets_dir = prepare_assets(
+            &assets_dir,
+            &game_dir,
+            effective_version_json.asset_index_id(),
+        )?;
+
         // Обновляем options с правильным путём к Java
         let mut options = options;
         options.java_path = Some(java_cmd.clone());
@@ -161,29 +285,71 @@ impl MinecraftLauncher {
             version_jar_path,
             natives_dir,
             assets_dir,
+            game_assets_dir,
             game_dir,
             effective_version_json,
+            java_major,
+            client_version: installer.get_installed_client_version(),
         };
 
         let mut cmd = build_java_command(&params, &options)?;
 
+        if options.dry_run.unwrap_or(false) {
+            let command = cmd.describe();
+            app.emit(
+                "minecraft-log",
+                LogEvent {
+                    message: format!("Dry run, game not started: {}", command.command_line),
+                },
+            )?;
+            return Ok(LaunchOutcome::DryRun(command));
+        }
+
+        let session_log = open_session_log(&params);
+
+        // Хук до запуска: ненулевой код отменяет запуск
+        if let Some(hook) = options.pre_launch_hook.clone().filter(|hook| !hook.trim().is_empty()) {
+            let ctx = HookContext {
+                launch_dir: self.launch_dir.clone(),
+                game_dir: params.game_dir.clone(),
+                version_id: version_id.clone(),
+                java_path: java_cmd.clone(),
+            };
+            let timeout = hook_timeout(&options);
+            let hook_app = app.clone();
+            let hook_log = session_log.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                run_hook(&hook_app, HookStage::PreLaunch, &hook, &ctx, &[], timeout, hook_log.as_ref())
+            })
+            .await?;
+            if let Err(e) = result {
+                let error_msg = format!("Launch aborted by pre-launch hook: {}", e);
+                app.emit("minecraft-log", LogEvent { message: error_msg.clone() })?;
+                return Err(anyhow::anyhow!(error_msg));
+            }
+        }
+
         emit_pre_launch(&app, &options.username)?;
 
-        match cmd.spawn() {
+        let has_wrappers = options.wrapper_commands.as_ref().is_some_and(|w| !w.is_empty());
+        let pid = match cmd.spawn() {
             Ok(child) => {
-                spawn_and_monitor(child, &params, &app)?;
+                let pid = spawn_and_monitor(child, reservation, &params, &options, session_log, &app);
                 emit_launch_success(&app)?;
+                pid
             }
             Err(e) => {
-                let error_msg = format!(
-                    "✗ Failed to launch Minecraft: {}. Check if Java is installed.",
-                    e
-                );
+                let hint = if has_wrappers {
+                    "Check the wrapper commands and that Java is installed."
+                } else {
+                    "Check if Java is installed."
+                };
+                let error_msg = format!("✗ Failed to launch Minecraft: {}. {}", e, hint);
                 app.emit("minecraft-log", LogEvent { message: error_msg.clone() })?;
                 return Err(anyhow::anyhow!(error_msg));
             }
-        }
+        };
 
-        Ok(())
+        Ok(LaunchOutcome::Started { pid })
     }
 }
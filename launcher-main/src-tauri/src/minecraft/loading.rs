@@ -0,0 +1,444 @@
+//! Отслеживание загрузки игры по её логу: этапы из известных строк
+//! (Fabric, LWJGL, ресурсы, звук) уходят событием `minecraft-loading`,
+//! в конце — `ready` или `failed`. Без готовности за `LOADING_TIMEOUT` загрузка считается неудачной.
+//!
+//! Конец перезагрузки ресурсов игра в лог не пишет. Последнее, что она делает, —
+//! загружает атласы текстур (`Created: ...-atlas`) и запускает звук, после чего
+//! показывает главное меню и замолкает. Поэтому готовность — это тишина в логе
+//! в течение `RELOAD_SETTLE` после этих строк.
+
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Сколько ждать готовности окна
+const LOADING_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+const TIMEOUT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Сколько лог должен молчать после атласов и звука, чтобы перезагрузка считалась законченной
+const RELOAD_SETTLE: Duration = Duration::from_secs(3);
+/// Предел ожидания тишины: некоторые моды пишут в лог и в главном меню
+const RELOAD_SETTLE_MAX: Duration = Duration::from_secs(30);
+
+/// Вехи загрузки: шаблон сообщения (без времени и потока), этап, прогресс, последний ли этап
+/// перезагрузки ресурсов. Шаблоны привязаны к началу строки, чтобы не срабатывать на строках модов.
+const MILESTONES: [(&str, &str, f64, bool); 7] = [
+    (
+        r"^Loading Minecraft \S+ with Fabric Loader ",
+        "loader",
+        10.0,
+        false,
+    ),
+    (r"^Setting user: ", "user", 30.0, false),
+    // Пишется прямо перед созданием окна: 1.19+ и старые LWJGL 3
+    (r"^Backend library: LWJGL version ", "window", 45.0, false),
+    (r"^LWJGL Version: ", "window", 45.0, false),
+    (r"^Reloading ResourceManager: ", "resources", 60.0, false),
+    (r"^Created: \d+x\d+x\d+ \S+-atlas$", "textures", 75.0, true),
+    (r"^Sound engine started$", "sound", 85.0, true),
+];
+
+/// Строки, после которых игра уже не загрузится
+const FAILURE_MARKERS: [&str; 4] = [
+    "---- Minecraft Crash Report ----",
+    "#@!@# Game crashed!",
+    "Exception in thread \"main\"",
+    "Incompatible mods found!",
+];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LoadingState {
+    Loading,
+    Ready,
+    Failed,
+}
+
+/// Событие `minecraft-loading`
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadingEvent {
+    /// false — загрузка закончилась (готово или ошибка), индикатор можно прятать
+    pub loading: bool,
+    pub state: LoadingState,
+    pub stage: String,
+    pub progress: f64,
+    pub message: Option<String>,
+}
+
+impl LoadingEvent {
+    fn new(state: LoadingState, stage: &str, progress: f64, message: Option<String>) -> Self {
+        Self {
+            loading: state == LoadingState::Loading,
+            state,
+            stage: stage.to_string(),
+            progress,
+            message,
+        }
+    }
+}
+
+/// Разбор загрузки по строкам лога без потоков и событий: время передаётся снаружи,
+/// каждый шаг возвращает событие, которое нужно отправить (если оно есть).
+pub struct LoadingProgress {
+    progress: f64,
+    finished: bool,
+    open_streams: u32,
+    started: Instant,
+    /// Первая и последняя строки лога начиная с атласов/звука; None — до них ещё не дошло
+    reload_tail: Option<(Instant, Instant)>,
+}
+
+impl LoadingProgress {
+    pub fn new(streams: u32, now: Instant) -> Self {
+        Self {
+            progress: 0.0,
+            finished: false,
+            open_streams: streams,
+            started: now,
+            reload_tail: None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Проверяет очередную строку лога на вехи и признаки падения
+    pub fn observe(&mut self, message: &str, now: Instant) -> Option<LoadingEvent> {
+        if FAILURE_MARKERS
+            .iter()
+            .any(|marker| message.contains(marker))
+        {
+            return self.fail(message.trim().to_string());
+        }
+
+        let milestone = milestones()
+            .iter()
+            .find(|(pattern, ..)| pattern.is_match(message));
+
+        // Пока после атласов идут строки, перезагрузка ещё не закончена
+        match self.reload_tail.as_mut() {
+            Some((_, last)) => *last = now,
+            None if milestone.is_some_and(|(.., tail)| *tail) => {
+                self.reload_tail = Some((now, now))
+            }
+            None => {}
+        }
+
+        if let Some(caps) = mods_pattern().captures(message) {
+            return self.advance("mods", 20.0, Some(format!("Loading {} mods", &caps[1])));
+        }
+
+        let (_, stage, progress, _) = milestone?;
+        self.advance(stage, *progress, None)
+    }
+
+    /// Проверка по времени: лог затих после атласов и звука — готово, вышел таймаут — ошибка
+    pub fn tick(&mut self, now: Instant) -> Option<LoadingEvent> {
+        if self.finished {
+            return None;
+        }
+
+        let settled = self.reload_tail.is_some_and(|(first, last)| {
+            now.saturating_duration_since(last) >= RELOAD_SETTLE
+                || now.saturating_duration_since(first) >= RELOAD_SETTLE_MAX
+        });
+        if settled {
+            return self.advance("ready", 100.0, None);
+        }
+
+        if now.saturating_duration_since(self.started) >= LOADING_TIMEOUT {
+            return self.fail(format!(
+                "The game window did not appear within {} minutes",
+                LOADING_TIMEOUT.as_secs() / 60
+            ));
+        }
+        None
+    }
+
+    /// Поток вывода закрылся; когда закрыты все, процесс завершился
+    pub fn stream_closed(&mut self) -> Option<LoadingEvent> {
+        self.open_streams = self.open_streams.saturating_sub(1);
+        if self.open_streams > 0 {
+            return None;
+        }
+        self.fail("The game exited before the window appeared".to_string())
+    }
+
+    fn advance(
+        &mut self,
+        stage: &str,
+        value: f64,
+        message: Option<String>,
+    ) -> Option<LoadingEvent> {
+        // Этапы только растут: строки stdout и stderr приходят вперемешку
+        if self.finished || value <= self.progress {
+            return None;
+        }
+        self.progress = value;
+        self.finished = value >= 100.0;
+
+        let state = if self.finished {
+            LoadingState::Ready
+        } else {
+            LoadingState::Loading
+        };
+        Some(LoadingEvent::new(state, stage, value, message))
+    }
+
+    fn fail(&mut self, message: String) -> Option<LoadingEvent> {
+        if self.finished {
+            return None;
+        }
+        self.finished = true;
+        Some(LoadingEvent::new(
+            LoadingState::Failed,
+            "failed",
+            self.progress,
+            Some(message),
+        ))
+    }
+}
+
+/// Общий для stdout и stderr наблюдатель загрузки
+pub struct LoadingTracker<R: Runtime> {
+    app: AppHandle<R>,
+    progress: Mutex<LoadingProgress>,
+}
+
+impl<R: Runtime> LoadingTracker<R> {
+    /// Сообщает о начале загрузки и запускает таймаут. `streams` — сколько потоков вывода читается.
+    pub fn start(app: &AppHandle<R>, streams: u32) -> Arc<Self> {
+        let tracker = Arc::new(Self {
+            app: app.clone(),
+            progress: Mutex::new(LoadingProgress::new(streams, Instant::now())),
+        });
+        tracker.emit(Some(LoadingEvent::new(
+            LoadingState::Loading,
+            "starting",
+            0.0,
+            None,
+        )));
+
+        let watcher = tracker.clone();
+        thread::spawn(move || loop {
+            let (event, finished) = {
+                let mut progress = watcher.progress.lock().unwrap();
+                let event = progress.tick(Instant::now());
+                (event, progress.is_finished())
+            };
+            watcher.emit(event);
+            if finished {
+                return;
+            }
+            thread::sleep(TIMEOUT_POLL_INTERVAL);
+        });
+
+        tracker
+    }
+
+    pub fn observe(&self, message: &str) {
+        let event = self
+            .progress
+            .lock()
+            .unwrap()
+            .observe(message, Instant::now());
+        self.emit(event);
+    }
+
+    pub fn stream_closed(&self) {
+        let event = self.progress.lock().unwrap().stream_closed();
+        self.emit(event);
+    }
+
+    fn emit(&self, event: Option<LoadingEvent>) {
+        if let Some(event) = event {
+            let _ = self.app.emit("minecraft-loading", event);
+        }
+    }
+}
+
+/// Fabric: "Loading 42 mods:"
+fn mods_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^Loading (\d+) mods:").expect("valid mods pattern"))
+}
+
+fn milestones() -> &'static [(Regex, &'static str, f64, bool)] {
+    static PATTERNS: OnceLock<Vec<(Regex, &'static str, f64, bool)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        MILESTONES
+            .iter()
+            .map(|(pattern, stage, progress, tail)| {
+                let regex = Regex::new(pattern).expect("valid milestone pattern");
+                (regex, *stage, *progress, *tail)
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minecraft::game_log::LogParser;
+
+    /// Консоль Fabric 1.21.4 от запуска до главного меню (сокращено)
+    const FABRIC_1_21_4_LOG: &str = "\
+[18:02:11] [main/INFO]: Loading Minecraft 1.21.4 with Fabric Loader 0.16.9
+[18:02:11] [main/INFO]: Loading 4 mods:
+\t- fabric-api 0.112.0+1.21.4
+\t- fabricloader 0.16.9
+\t- java 21
+\t- minecraft 1.21.4
+[18:02:11] [main/INFO]: SpongePowered MIXIN Subsystem Version=0.8.7 Source=file:/home/steve/.minecraft/libraries/net/fabricmc/sponge-mixin/0.15.4+mixin.0.8.7/sponge-mixin-0.15.4+mixin.0.8.7.jar Service=Knot/Fabric Env=CLIENT
+[18:02:12] [main/INFO]: Compatibility level set to JAVA_21
+[18:02:15] [Datafixer Bootstrap/INFO]: 248 Datafixer optimizations took 512 milliseconds
+[18:02:17] [Render thread/INFO]: Environment: Environment[sessionHost=https://sessionserver.mojang.com, servicesHost=https://api.minecraftservices.com, name=PROD]
+[18:02:17] [Render thread/INFO]: Setting user: Steve
+[18:02:17] [Render thread/INFO]: [Indigo] Registering Indigo renderer!
+[18:02:18] [Render thread/INFO]: Backend library: LWJGL version 3.3.3-snapshot
+[18:02:18] [Render thread/INFO]: Using optional rendering extensions: GL_KHR_debug, GL_ARB_vertex_attrib_binding, GL_ARB_direct_state_access
+[18:02:19] [Render thread/INFO]: Reloading ResourceManager: vanilla, fabric, fabric-api, fabric-api-base
+[18:02:20] [Worker-Main-5/INFO]: Found unifont_all_no_pua-16.0.01.hex, loading
+[18:02:22] [Render thread/WARN]: Missing sound for event: minecraft:item.goat_horn.play
+[18:02:23] [Render thread/INFO]: OpenAL initialized on device OpenAL Soft
+[18:02:23] [Render thread/INFO]: Sound engine started
+[18:02:23] [Render thread/INFO]: Created: 1024x512x4 minecraft:textures/atlas/blocks.png-atlas
+[18:02:23] [Render thread/INFO]: Created: 256x256x4 minecraft:textures/atlas/signs.png-atlas
+[18:02:24] [Render thread/INFO]: Created: 512x256x0 minecraft:textures/atlas/particles.png-atlas
+";
+
+    /// Прогоняет лог через парсер; строки идут с интервалом 100 мс. Возвращает время последней.
+    fn feed(
+        loading: &mut LoadingProgress,
+        log: &str,
+        start: Instant,
+        events: &mut Vec<LoadingEvent>,
+    ) -> Instant {
+        let mut parser = LogParser::new("stdout", false);
+        let mut now = start;
+        for line in log.lines() {
+            now += Duration::from_millis(100);
+            if let Some(line) = parser.push(line) {
+                events.extend(loading.observe(&line.message, now));
+            }
+        }
+        now
+    }
+
+    #[test]
+    fn fabric_1_21_4_log_reaches_ready_after_settle() {
+        let start = Instant::now();
+        let mut loading = LoadingProgress::new(2, start);
+        let mut events = Vec::new();
+        let last = feed(&mut loading, FABRIC_1_21_4_LOG, start, &mut events);
+
+        let stages: Vec<&str> = events.iter().map(|event| event.stage.as_str()).collect();
+        assert_eq!(
+            stages,
+            vec!["loader", "mods", "user", "window", "resources", "sound"]
+        );
+        assert_eq!(events[1].message.as_deref(), Some("Loading 4 mods"));
+        assert!(events.iter().all(|event| event.loading));
+
+        // Атласы после звука не двигают прогресс назад, но продлевают ожидание тишины
+        assert!(loading
+            .tick(last + RELOAD_SETTLE - Duration::from_millis(1))
+            .is_none());
+        let ready = loading.tick(last + RELOAD_SETTLE).unwrap();
+        assert_eq!(ready.state, LoadingState::Ready);
+        assert!(!ready.loading);
+        assert_eq!(ready.progress, 100.0);
+
+        assert!(loading.is_finished());
+        assert!(loading.tick(last + LOADING_TIMEOUT).is_none());
+        assert!(loading.stream_closed().is_none());
+    }
+
+    #[test]
+    fn milestones_match_only_at_line_start() {
+        let now = Instant::now();
+        let mut loading = LoadingProgress::new(1, now);
+
+        for message in [
+            "[Fabric] Loading Minecraft 1.21.4 with Fabric Loader 0.16.9",
+            "Mod menu: Loading Minecraft 1.21.4 with Fabric Loader 0.16.9",
+            "[Sodium] Setting user: Steve",
+            "Skipping: Sound engine started",
+            "Created: 1024x512x4 minecraft:textures/atlas/blocks.png-atlas (cached)",
+        ] {
+            assert!(loading.observe(message, now).is_none(), "{}", message);
+        }
+        // Без атласов и звука тишина в логе готовностью не считается
+        assert!(loading.tick(now + RELOAD_SETTLE_MAX).is_none());
+
+        let event = loading
+            .observe("Loading Minecraft 1.21.4 with Fabric Loader 0.16.9", now)
+            .unwrap();
+        assert_eq!(event.stage, "loader");
+    }
+
+    #[test]
+    fn chatty_mods_cannot_delay_ready_forever() {
+        let start = Instant::now();
+        let mut loading = LoadingProgress::new(1, start);
+        loading.observe("Sound engine started", start);
+
+        let mut now = start;
+        while now < start + RELOAD_SETTLE_MAX - Duration::from_secs(1) {
+            now += Duration::from_secs(1);
+            loading.observe("[ModMenu] Checking for updates", now);
+            assert!(loading.tick(now).is_none());
+        }
+
+        let ready = loading.tick(start + RELOAD_SETTLE_MAX).unwrap();
+        assert_eq!(ready.state, LoadingState::Ready);
+    }
+
+    #[test]
+    fn crash_markers_fail_loading() {
+        let now = Instant::now();
+        let mut loading = LoadingProgress::new(1, now);
+        loading.observe("Setting user: Steve", now);
+
+        let failed = loading
+            .observe(
+                "#@!@# Game crashed! Crash report saved to: #@!@# crash.txt",
+                now,
+            )
+            .unwrap();
+        assert_eq!(failed.state, LoadingState::Failed);
+        assert_eq!(failed.progress, 30.0);
+        assert!(loading.observe("Sound engine started", now).is_none());
+    }
+
+    #[test]
+    fn times_out_without_ready() {
+        let start = Instant::now();
+        let mut loading = LoadingProgress::new(1, start);
+        loading.observe("Setting user: Steve", start);
+
+        assert!(loading
+            .tick(start + LOADING_TIMEOUT - Duration::from_secs(1))
+            .is_none());
+        let failed = loading.tick(start + LOADING_TIMEOUT).unwrap();
+        assert_eq!(failed.state, LoadingState::Failed);
+        assert_eq!(
+            failed.message.as_deref(),
+            Some("The game window did not appear within 5 minutes")
+        );
+    }
+
+    #[test]
+    fn fails_when_every_stream_closes() {
+        let mut loading = LoadingProgress::new(2, Instant::now());
+
+        assert!(loading.stream_closed().is_none());
+        let failed = loading.stream_closed().unwrap();
+        assert_eq!(failed.state, LoadingState::Failed);
+    }
+}
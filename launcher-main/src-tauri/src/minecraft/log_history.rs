@@ -0,0 +1,416 @@
+//! История логов запусков. Текущий запуск пишет в `run/logs/launcher-java.log`,
+//! перед следующим запуском файл сжимается в `run/logs/sessions/` — так вывод
+//! прошлой сессии не теряется при повторном запуске.
+
+use std::cmp::Reverse;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+
+/// Лог текущего (или последнего) запуска
+pub const CURRENT_LOG: &str = "launcher-java.log";
+/// ID текущего лога в командах
+pub const CURRENT_SESSION: &str = "current";
+
+const SESSIONS_DIR: &str = "sessions";
+const SESSION_PREFIX: &str = "launcher-java-";
+const SESSION_SUFFIX: &str = ".log.gz";
+/// Сколько старых сессий хранить
+const MAX_SESSIONS: usize = 30;
+/// Предел общего размера сжатых логов
+const MAX_HISTORY_BYTES: u64 = 50 * 1024 * 1024;
+/// Размер страницы по умолчанию и максимальный
+pub const DEFAULT_PAGE_SIZE: usize = 500;
+const MAX_PAGE_SIZE: usize = 5000;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSession {
+    pub id: String,
+    /// Время последней записи, мс с начала эпохи
+    pub modified_at: u64,
+    /// Размер на диске (для старых сессий — сжатый)
+    pub size: u64,
+    pub compressed: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLine {
+    /// Номер строки в логе, с 1
+    pub number: usize,
+    pub text: String,
+}
+
+/// Страница строк лога или результатов поиска
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogPage {
+    pub session: String,
+    pub offset: usize,
+    /// Всего строк (для поиска — совпадений)
+    pub total: usize,
+    pub lines: Vec<LogLine>,
+}
+
+/// Открытый лог текущего запуска: в него пишут игра и хуки
+pub type SharedLog = Arc<Mutex<File>>;
+
+/// Начинает лог нового запуска: прошлый уходит в историю, новый открывается с заголовком
+pub fn start_session(logs_dir: &Path, header: &str) -> Option<SharedLog> {
+    let _ = fs::create_dir_all(logs_dir);
+    if let Err(e) = rotate(logs_dir) {
+        log::warn!("Failed to rotate game log: {}", e);
+    }
+
+    let path = logs_dir.join(CURRENT_LOG);
+    let _ = fs::write(&path, header);
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .ok()
+        .map(|f| Arc::new(Mutex::new(f)))
+}
+
+/// Дописывает строку в лог запуска, если он открыт
+pub fn append_line(log: Option<&SharedLog>, line: &str) {
+    if let Some(file) = log {
+        if let Ok(mut file) = file.lock() {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+/// Переносит лог прошлого запуска в историю и удаляет то, что не влезает в лимиты
+pub fn rotate(logs_dir: &Path) -> anyhow::Result<()> {
+    let current = logs_dir.join(CURRENT_LOG);
+    let metadata = match fs::metadata(&current) {
+        Ok(metadata) if metadata.len() > 0 => metadata,
+        _ => return Ok(()),
+    };
+
+    let sessions_dir = logs_dir.join(SESSIONS_DIR);
+    fs::create_dir_all(&sessions_dir)?;
+
+    let modified = modified_millis(&metadata);
+    let dest = sessions_dir.join(format!("{SESSION_PREFIX}{modified}{SESSION_SUFFIX}"));
+    let part = dest.with_extension("gz.part");
+
+    let mut encoder = GzEncoder::new(File::create(&part)?, Compression::default());
+    io::copy(&mut File::open(&current)?, &mut encoder)?;
+    encoder.finish()?;
+    fs::rename(&part, &dest)?;
+    fs::remove_file(&current)?;
+
+    prune(&sessions_dir);
+    Ok(())
+}
+
+/// Оставляет самые новые сессии в пределах `MAX_SESSIONS` и `MAX_HISTORY_BYTES`
+fn prune(sessions_dir: &Path) {
+    let mut total = 0;
+    for (index, session) in archived_sessions(sessions_dir).iter().enumerate() {
+        total += session.size;
+        if index >= MAX_SESSIONS || total > MAX_HISTORY_BYTES {
+            if let Err(e) = fs::remove_file(session_path(sessions_dir, &session.id)) {
+                log::warn!("Failed to remove old log {}: {}", session.id, e);
+            }
+        }
+    }
+}
+
+/// Все сессии, начиная с текущей и далее от новых к старым
+pub fn list_sessions(logs_dir: &Path) -> Vec<LogSession> {
+    let mut sessions = Vec::new();
+    if let Ok(metadata) = fs::metadata(logs_dir.join(CURRENT_LOG)) {
+        sessions.push(LogSession {
+            id: CURRENT_SESSION.to_string(),
+            modified_at: modified_millis(&metadata),
+            size: metadata.len(),
+            compressed: false,
+        });
+    }
+    sessions.extend(archived_sessions(&logs_dir.join(SESSIONS_DIR)));
+    sessions
+}
+
+fn archived_sessions(sessions_dir: &Path) -> Vec<LogSession> {
+    let entries = match fs::read_dir(sessions_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut sessions: Vec<LogSession> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let id = name.strip_suffix(SESSION_SUFFIX)?;
+            if !id.starts_with(SESSION_PREFIX) {
+                return None;
+            }
+            let metadata = entry.metadata().ok()?;
+            Some(LogSession {
+                id: id.to_string(),
+                modified_at: modified_millis(&metadata),
+                size: metadata.len(),
+                compressed: true,
+            })
+        })
+        .collect();
+
+    sessions.sort_by_key(|session| Reverse(session.modified_at));
+    sessions
+}
+
+fn session_path(sessions_dir: &Path, id: &str) -> PathBuf {
+    sessions_dir.join(format!("{id}{SESSION_SUFFIX}"))
+}
+
+/// Все строки сессии. ID берётся только из списка, чтобы нельзя было выйти за папку логов.
+fn read_lines(logs_dir: &Path, session: &str) -> anyhow::Result<Vec<String>> {
+    let found = list_sessions(logs_dir)
+        .into_iter()
+        .find(|s| s.id == session)
+        .ok_or_else(|| anyhow::anyhow!("Log session {} not found", session))?;
+
+    let mut bytes = Vec::new();
+    if found.compressed {
+        let file = File::open(session_path(&logs_dir.join(SESSIONS_DIR), &found.id))?;
+        GzDecoder::new(BufReader::new(file)).read_to_end(&mut bytes)?;
+    } else {
+        bytes = fs::read(logs_dir.join(CURRENT_LOG))?;
+    }
+
+    Ok(String::from_utf8_lossy(&bytes)
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Страница строк лога начиная с `offset`
+pub fn read_session(
+    logs_dir: &Path,
+    session: &str,
+    offset: usize,
+    limit: usize,
+) -> anyhow::Result<LogPage> {
+    let lines = read_lines(logs_dir, session)?;
+    let total = lines.len();
+    let lines = lines
+        .into_iter()
+        .enumerate()
+        .skip(offset)
+        .take(limit.clamp(1, MAX_PAGE_SIZE))
+        .map(|(index, text)| LogLine {
+            number: index + 1,
+            text,
+        })
+        .collect();
+
+    Ok(LogPage {
+        session: session.to_string(),
+        offset,
+        total,
+        lines,
+    })
+}
+
+/// Поиск подстроки без учёта регистра; `offset` и `limit` считаются по совпадениям
+pub fn search_session(
+    logs_dir: &Path,
+    session: &str,
+    query: &str,
+    offset: usize,
+    limit: usize,
+) -> anyhow::Result<LogPage> {
+    let query = query.to_lowercase();
+    let matches: Vec<LogLine> = read_lines(logs_dir, session)?
+        .into_iter()
+        .enumerate()
+        .filter(|(_, text)| text.to_lowercase().contains(&query))
+        .map(|(index, text)| LogLine {
+            number: index + 1,
+            text,
+        })
+        .collect();
+
+    Ok(LogPage {
+        session: session.to_string(),
+        offset,
+        total: matches.len(),
+        lines: matches
+            .into_iter()
+            .skip(offset)
+            .take(limit.clamp(1, MAX_PAGE_SIZE))
+            .collect(),
+    })
+}
+
+fn modified_millis(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn temp_logs_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("launcher-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join(SESSIONS_DIR)).unwrap();
+        dir
+    }
+
+    /// Архивная сессия с заданным временем изменения; `size` задаёт длину без записи данных
+    fn archived(logs_dir: &Path, id: &str, age_secs: u64, size: u64) {
+        let file = File::create(session_path(&logs_dir.join(SESSIONS_DIR), id)).unwrap();
+        file.set_len(size).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(age_secs))
+            .unwrap();
+    }
+
+    fn archived_ids(logs_dir: &Path) -> Vec<String> {
+        archived_sessions(&logs_dir.join(SESSIONS_DIR))
+            .into_iter()
+            .map(|session| session.id)
+            .collect()
+    }
+
+    #[test]
+    fn rotate_compresses_previous_log() {
+        let dir = temp_logs_dir();
+        fs::write(dir.join(CURRENT_LOG), "first line\nsecond line\n").unwrap();
+
+        rotate(&dir).unwrap();
+
+        assert!(!dir.join(CURRENT_LOG).exists());
+        let sessions = list_sessions(&dir);
+        assert_eq!(sessions.len(), 1);
+        assert!(sessions[0].compressed);
+        assert!(sessions[0].id.starts_with(SESSION_PREFIX));
+
+        let mut text = String::new();
+        let file = File::open(session_path(&dir.join(SESSIONS_DIR), &sessions[0].id)).unwrap();
+        GzDecoder::new(file).read_to_string(&mut text).unwrap();
+        assert_eq!(text, "first line\nsecond line\n");
+
+        let page = read_session(&dir, &sessions[0].id, 0, 10).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.lines[1].text, "second line");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rotate_skips_empty_log() {
+        let dir = temp_logs_dir();
+        fs::write(dir.join(CURRENT_LOG), "").unwrap();
+
+        rotate(&dir).unwrap();
+
+        assert!(archived_ids(&dir).is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn prune_keeps_newest_sessions() {
+        let dir = temp_logs_dir();
+        for age in 0..MAX_SESSIONS as u64 + 2 {
+            archived(&dir, &format!("{SESSION_PREFIX}{age}"), age * 60, 100);
+        }
+
+        prune(&dir.join(SESSIONS_DIR));
+
+        let ids = archived_ids(&dir);
+        assert_eq!(ids.len(), MAX_SESSIONS);
+        assert_eq!(ids.first().unwrap(), &format!("{SESSION_PREFIX}0"));
+        assert_eq!(ids.last().unwrap(), &format!("{SESSION_PREFIX}29"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn prune_keeps_history_under_size_limit() {
+        let dir = temp_logs_dir();
+        let size = 20 * 1024 * 1024;
+        archived(&dir, "launcher-java-new", 0, size);
+        archived(&dir, "launcher-java-middle", 60, size);
+        archived(&dir, "launcher-java-old", 120, size);
+
+        prune(&dir.join(SESSIONS_DIR));
+
+        assert_eq!(
+            archived_ids(&dir),
+            vec!["launcher-java-new", "launcher-java-middle"]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn session_id_cannot_escape_logs_dir() {
+        let dir = temp_logs_dir();
+        let logs_dir = dir.join("logs");
+        fs::create_dir_all(&logs_dir).unwrap();
+        // Файл, на который указал бы `../`, существует, но в список сессий не входит
+        let mut encoder = GzEncoder::new(
+            File::create(dir.join("secret.log.gz")).unwrap(),
+            Compression::default(),
+        );
+        encoder.write_all(b"secret\n").unwrap();
+        encoder.finish().unwrap();
+
+        for id in ["../secret", "../../secret", "sessions/../../secret"] {
+            assert!(read_session(&logs_dir, id, 0, 10).is_err());
+            assert!(search_session(&logs_dir, id, "secret", 0, 10).is_err());
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn read_session_pages_lines() {
+        let dir = temp_logs_dir();
+        let text: String = (1..=10).map(|n| format!("line {n}\n")).collect();
+        fs::write(dir.join(CURRENT_LOG), text).unwrap();
+
+        let page = read_session(&dir, CURRENT_SESSION, 3, 4).unwrap();
+        assert_eq!(page.total, 10);
+        assert_eq!(page.offset, 3);
+        let numbers: Vec<usize> = page.lines.iter().map(|line| line.number).collect();
+        assert_eq!(numbers, vec![4, 5, 6, 7]);
+        assert_eq!(page.lines[0].text, "line 4");
+
+        // limit меньше 1 поднимается до одной строки
+        assert_eq!(
+            read_session(&dir, CURRENT_SESSION, 0, 0)
+                .unwrap()
+                .lines
+                .len(),
+            1
+        );
+        assert!(read_session(&dir, CURRENT_SESSION, 10, 5)
+            .unwrap()
+            .lines
+            .is_empty());
+
+        let page = search_session(&dir, CURRENT_SESSION, "LINE 1", 1, 5).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.lines.len(), 1);
+        assert_eq!(page.lines[0].number, 10);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
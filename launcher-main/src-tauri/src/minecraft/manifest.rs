@@ -0,0 +1,218 @@
+//! Клиент манифеста версий Mojang с дисковым кешем.
+//!
+//! Манифест хранится в `versions/version_manifest_v2.json` и перепроверяется
+//! по ETag/Last-Modified. JSON версий лежат в `versions/<id>/<id>.json` и сверяются
+//! с `sha1` из манифеста. Без сети используется то, что уже есть на диске.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use super::model::{parse_json, read_json_file, VersionJson, VersionManifest};
+use crate::client_installer::sha1_hex;
+
+pub(crate) const VERSION_MANIFEST_URL: &str =
+    "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+
+const MANIFEST_FILE: &str = "version_manifest_v2.json";
+const MANIFEST_CACHE_FILE: &str = "version_manifest_v2.cache.json";
+
+/// Заголовки ответа, по которым перепроверяется закешированный манифест
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct CacheValidators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+pub struct ManifestClient {
+    client: Client,
+    versions_dir: PathBuf,
+    manifest_url: String,
+}
+
+impl ManifestClient {
+    pub fn new(client: Client, launch_dir: &Path, manifest_url: &str) -> Self {
+        Self {
+            client,
+            versions_dir: launch_dir.join("versions"),
+            manifest_url: manifest_url.to_string(),
+        }
+    }
+
+    /// Манифест версий: свежий из сети, а при ошибке сети — из кеша
+    pub async fn version_manifest(&self) -> anyhow::Result<VersionManifest> {
+        let manifest_path = self.versions_dir.join(MANIFEST_FILE);
+
+        match self.fetch_manifest(&manifest_path).await {
+            Ok(manifest) => Ok(manifest),
+            Err(network_error) if manifest_path.exists() => {
+                log::warn!(
+                    "Version manifest refresh failed, using cached copy: {}",
+                    network_error
+                );
+                read_json_file(&manifest_path)
+            }
+            Err(network_error) => Err(anyhow::anyhow!(
+                "Version manifest is unavailable: {} (no cached copy in {})",
+                network_error,
+                manifest_path.display()
+            )),
+        }
+    }
+
+    /// Гарантирует наличие `versions/<id>/<id>.json`, совпадающего с манифестом.
+    /// Версии, которых нет в манифесте (профили загрузчиков), берутся с диска как есть.
+    pub async fn ensure_version_json(&self, version_id: &str) -> anyhow::Result<PathBuf> {
+        let version_json_path = self
+            .versions_dir
+            .join(version_id)
+            .join(format!("{version_id}.json"));
+
+        let manifest = match self.version_manifest().await {
+            Ok(manifest) => manifest,
+            Err(e) if version_json_path.exists() => {
+                log::warn!("Using cached JSON for version {}: {}", version_id, e);
+                return Ok(version_json_path);
+            }
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Version {} is not installed and cannot be downloaded: {}",
+                    version_id,
+                    e
+                ))
+            }
+        };
+
+        let entry = match manifest.versions.iter().find(|v| v.id == version_id) {
+            Some(entry) => entry,
+            None if version_json_path.exists() => return Ok(version_json_path),
+            None => {
+                return Err(anyhow::anyhow!(
+                    "Version {} not found in the version manifest",
+                    version_id
+                ))
+            }
+        };
+
+        if let Ok(existing) = fs::read(&version_json_path) {
+            let up_to_date = match &entry.sha1 {
+                Some(expected) => sha1_hex(&existing).eq_ignore_ascii_case(expected),
+                None => true,
+            };
+            if up_to_date {
+                return Ok(version_json_path);
+            }
+        }
+
+        match self
+            .fetch_version_json(&entry.url, entry.sha1.as_deref(), version_id)
+            .await
+        {
+            Ok(raw) => {
+                write_atomically(&version_json_path, raw.as_bytes())?;
+                Ok(version_json_path)
+            }
+            Err(e) if version_json_path.exists() => {
+                log::warn!(
+                    "Failed to refresh JSON for version {}, using cached copy: {}",
+                    version_id,
+                    e
+                );
+                Ok(version_json_path)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Условный запрос манифеста. 304 — кеш актуален, 200 — обновляем кеш.
+    async fn fetch_manifest(&self, manifest_path: &Path) -> anyhow::Result<VersionManifest> {
+        let cache_path = self.versions_dir.join(MANIFEST_CACHE_FILE);
+        let validators: CacheValidators = if manifest_path.exists() {
+            read_json_file(&cache_path).unwrap_or_default()
+        } else {
+            CacheValidators::default()
+        };
+
+        let mut request = self.client.get(&self.manifest_url);
+        if let Some(etag) = &validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+
+        let response = request.send().await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return read_json_file(manifest_path);
+        }
+        let response = response.error_for_status()?;
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let validators = CacheValidators {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        };
+
+        let raw = response.text().await?;
+        let manifest: VersionManifest = parse_json(&raw, "version manifest")?;
+
+        write_atomically(manifest_path, raw.as_bytes())?;
+        fs::write(&cache_path, serde_json::to_vec(&validators)?)?;
+
+        Ok(manifest)
+    }
+
+    async fn fetch_version_json(
+        &self,
+        url: &str,
+        sha1: Option<&str>,
+        version_id: &str,
+    ) -> anyhow::Result<String> {
+        let raw = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        if let Some(expected) = sha1 {
+            let actual = sha1_hex(raw.as_bytes());
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(anyhow::anyhow!(
+                    "JSON for version {} failed SHA-1 check: expected {}, got {}",
+                    version_id,
+                    expected,
+                    actual
+                ));
+            }
+        }
+
+        parse_json::<VersionJson>(&raw, &format!("version JSON {}", version_id))?;
+        Ok(raw)
+    }
+}
+
+/// Пишет через временный файл, чтобы оборванная запись не портила кеш
+fn write_atomically(path: &Path, data: &[u8]) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut part_name = path.file_name().unwrap_or_default().to_os_string();
+    part_name.push(".part");
+    let part_path = path.with_file_name(part_name);
+    fs::write(&part_path, data)?;
+    fs::rename(&part_path, path)?;
+    Ok(())
+}
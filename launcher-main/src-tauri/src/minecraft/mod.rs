@@ -1,14 +1,30 @@
-mod classpath;
+mod arguments;
+mod assets;
+pub(crate) mod classpath;
 mod commands;
+mod crash;
+mod game_log;
+mod history;
+mod hooks;
+mod jvm;
 mod launcher;
+mod loading;
+mod log_history;
+pub(crate) mod manifest;
+pub(crate) mod model;
+mod natives;
 mod options;
 mod process;
+mod registry;
+pub(crate) mod rules;
 mod types;
-mod version;
+mod validation;
+pub(crate) mod version;
 
+pub use registry::GameRegistry;
 
 
-use tauri::{AppHandle, Runtime};
+use tauri::{AppHandle, Runtime, State};
 
 #[tauri::command]
 pub async fn launch_minecraft<R: Runtime>(
@@ -65,6 +81,43 @@ pub async fn install_and_launch<R: Runtime>(
     commands::install_and_launch(app, user_id, options).await
 }
 
+#[tauri::command]
+pub async fn install_vanilla_version<R: Runtime>(
+    app: AppHandle<R>,
+    version_id: String,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    commands::install_vanilla_version(app, version_id, install_path).await
+}
+
+#[tauri::command]
+pub async fn list_available_versions<R: Runtime>(
+    app: AppHandle<R>,
+    types: Option<Vec<String>>,
+    loader: Option<String>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    commands::list_available_versions(app, types, loader, install_path).await
+}
+
+#[tauri::command]
+pub async fn list_installed_versions<R: Runtime>(
+    app: AppHandle<R>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    commands::list_installed_versions(app, install_path).await
+}
+
+#[tauri::command]
+pub async fn validate_installation<R: Runtime>(
+    app: AppHandle<R>,
+    version_id: Option<String>,
+    install_path: Option<String>,
+    java_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    commands::validate_installation(app, version_id, install_path, java_path).await
+}
+
 #[tauri::command]
 pub async fn get_client_dirs<R: Runtime>(app: AppHandle<R>) -> Result<serde_json::Value, String> {
     commands::get_client_dirs(app).await
@@ -91,3 +144,77 @@ pub async fn check_java_status<R: Runtime>(app: AppHandle<R>) -> Result<serde_js
 pub async fn install_java<R: Runtime>(app: AppHandle<R>) -> Result<serde_json::Value, String> {
     commands::install_java(app).await
 }
+
+#[tauri::command]
+pub async fn list_running_games(state: State<'_, GameRegistry>) -> Result<serde_json::Value, String> {
+    commands::list_running_games(state).await
+}
+
+#[tauri::command]
+pub async fn kill_game(state: State<'_, GameRegistry>, pid: u32) -> Result<serde_json::Value, String> {
+    commands::kill_game(state, pid).await
+}
+
+#[tauri::command]
+pub async fn get_game_status(
+    state: State<'_, GameRegistry>,
+    pid: u32,
+) -> Result<serde_json::Value, String> {
+    commands::get_game_status(state, pid).await
+}
+
+#[tauri::command]
+pub async fn list_game_logs<R: Runtime>(
+    app: AppHandle<R>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    commands::list_game_logs(app, install_path).await
+}
+
+#[tauri::command]
+pub async fn read_game_log<R: Runtime>(
+    app: AppHandle<R>,
+    session: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    commands::read_game_log(app, session, offset, limit, install_path).await
+}
+
+#[tauri::command]
+pub async fn search_game_log<R: Runtime>(
+    app: AppHandle<R>,
+    session: Option<String>,
+    query: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    commands::search_game_log(app, session, query, offset, limit, install_path).await
+}
+
+#[tauri::command]
+pub async fn get_total_playtime<R: Runtime>(
+    app: AppHandle<R>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    commands::get_total_playtime(app, install_path).await
+}
+
+#[tauri::command]
+pub async fn get_last_played<R: Runtime>(
+    app: AppHandle<R>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    commands::get_last_played(app, install_path).await
+}
+
+#[tauri::command]
+pub async fn list_recent_sessions<R: Runtime>(
+    app: AppHandle<R>,
+    limit: Option<usize>,
+    install_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    commands::list_recent_sessions(app, limit, install_path).await
+}
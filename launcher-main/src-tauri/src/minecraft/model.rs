@@ -0,0 +1,416 @@
+//! Типизированная модель манифеста версий Mojang, version.json (Mojang и Fabric)
+//! и asset index. Общая для сборки classpath, аргументов и установщиков.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::rules::Platform;
+
+/// version_manifest_v2.json
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VersionManifest {
+    pub latest: LatestVersions,
+    pub versions: Vec<ManifestVersion>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LatestVersions {
+    pub release: String,
+    pub snapshot: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestVersion {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub url: String,
+    pub time: Option<String>,
+    pub release_time: Option<String>,
+    pub sha1: Option<String>,
+}
+
+/// version.json (vanilla, Fabric и другие профили с inheritsFrom)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionJson {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inherits_from: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub main_class: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minecraft_arguments: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Arguments>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset_index: Option<AssetIndexRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assets: Option<String>,
+    #[serde(default)]
+    pub libraries: Vec<Library>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downloads: Option<HashMap<String, Download>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub java_version: Option<JavaVersion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logging: Option<Logging>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jar: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    /// Поля, которые лаунчер не использует, но должен сохранить при мерже
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl VersionJson {
+    /// Загружает version.json с понятной ошибкой при повреждённом файле
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        read_json_file(path)
+    }
+
+    /// Asset index ID. Как и официальный лаунчер: `assetIndex.id`, затем `assets`, затем `legacy`.
+    pub fn asset_index_id(&self) -> &str {
+        self.asset_index
+            .as_ref()
+            .map(|ai| ai.id.as_str())
+            .or(self.assets.as_deref())
+            .unwrap_or("legacy")
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Arguments {
+    #[serde(default)]
+    pub game: Vec<Argument>,
+    #[serde(default)]
+    pub jvm: Vec<Argument>,
+}
+
+/// Элемент `arguments.game` / `arguments.jvm`: строка или условный аргумент
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Argument {
+    Plain(String),
+    Conditional {
+        #[serde(default)]
+        rules: Vec<Rule>,
+        value: ArgumentValue,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum ArgumentValue {
+    Single(String),
+    Many(Vec<String>),
+}
+
+impl ArgumentValue {
+    pub fn values(&self) -> &[String] {
+        match self {
+            ArgumentValue::Single(value) => std::slice::from_ref(value),
+            ArgumentValue::Many(values) => values,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    Allow,
+    Disallow,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Rule {
+    pub action: RuleAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os: Option<OsRule>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub features: Option<HashMap<String, bool>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OsRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// Библиотека из version.json. У Mojang путь и ссылка лежат в `downloads`,
+/// у Fabric — Maven-координаты в `name` и репозиторий в `url`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Library {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downloads: Option<LibraryDownloads>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rules: Option<Vec<Rule>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub natives: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extract: Option<ExtractRules>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+impl Library {
+    /// Путь основного jar относительно libraries/ (с разделителями `/`)
+    pub fn artifact_path(&self) -> Option<String> {
+        self.downloads
+            .as_ref()
+            .and_then(|d| d.artifact.as_ref())
+            .and_then(|a| a.path.clone())
+            .or_else(|| maven_artifact_path(&self.name))
+    }
+
+    /// Запись только с нативами старого формата (`natives` без `downloads.artifact`):
+    /// основного jar у неё нет, ни в classpath, ни в загрузки она не попадает
+    pub fn is_natives_only(&self) -> bool {
+        self.natives.is_some()
+            && self
+                .downloads
+                .as_ref()
+                .and_then(|d| d.artifact.as_ref())
+                .is_none()
+    }
+
+    /// Ключ для разрешения конфликтов между уровнями inheritsFrom: `group:artifact[:classifier]`
+    pub fn conflict_key(&self) -> String {
+        let name = self.name.split('@').next().unwrap_or_default();
+        let parts: Vec<&str> = name.split(':').collect();
+        match parts.as_slice() {
+            [group, artifact, _version, classifier, ..] => {
+                format!("{group}:{artifact}:{classifier}")
+            }
+            [group, artifact, ..] => format!("{group}:{artifact}"),
+            _ => name.to_string(),
+        }
+    }
+
+    /// Классификатор нативов старого формата (`natives`) для платформы, с подстановкой `${arch}`
+    pub fn native_classifier(&self, platform: &Platform) -> Option<String> {
+        let classifier = self.natives.as_ref()?.get(&platform.os_name)?;
+        Some(classifier.replace("${arch}", platform.bits()))
+    }
+
+    /// Jar с нативами из `downloads.classifiers`
+    pub fn native_artifact(&self, platform: &Platform) -> Option<&Artifact> {
+        let classifier = self.native_classifier(platform)?;
+        self.downloads
+            .as_ref()?
+            .classifiers
+            .as_ref()?
+            .get(&classifier)
+    }
+
+    /// Путь jar с нативами относительно libraries/
+    pub fn native_artifact_path(&self, platform: &Platform) -> Option<String> {
+        self.native_artifact(platform)
+            .and_then(|a| a.path.clone())
+            .or_else(|| {
+                let classifier = self.native_classifier(platform)?;
+                maven_artifact_path(&format!("{}:{}", self.name, classifier))
+            })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LibraryDownloads {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifact: Option<Artifact>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classifiers: Option<HashMap<String, Artifact>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Artifact {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ExtractRules {
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// Файл с проверкой: client jar, asset index, конфиг логирования
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Download {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetIndexRef {
+    pub id: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_size: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JavaVersion {
+    pub component: Option<String>,
+    pub major_version: u32,
+}
+
+impl JavaVersion {
+    /// Java для версий без `javaVersion` (до 1.17), как в официальном лаунчере
+    pub fn legacy() -> Self {
+        Self {
+            component: Some("jre-legacy".to_string()),
+            major_version: 8,
+        }
+    }
+
+    /// Компонент Mojang runtime. Если в JSON его нет — по номеру версии Java.
+    pub fn component_name(&self) -> Option<&str> {
+        if let Some(component) = self.component.as_deref() {
+            return Some(component);
+        }
+        match self.major_version {
+            8 => Some("jre-legacy"),
+            16 => Some("java-runtime-alpha"),
+            17 => Some("java-runtime-gamma"),
+            21 => Some("java-runtime-delta"),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Logging {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client: Option<LoggingConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LoggingConfig {
+    pub argument: String,
+    pub file: LoggingFile,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+impl LoggingConfig {
+    /// Куда кладётся конфиг логирования: assets/log_configs/<id>
+    pub fn config_path(&self, assets_dir: &Path) -> PathBuf {
+        assets_dir.join("log_configs").join(&self.file.id)
+    }
+
+    /// Конфиг переключает вывод игры в XML-события log4j
+    pub fn is_xml(&self) -> bool {
+        self.kind == "log4j2-xml"
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LoggingFile {
+    pub id: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+/// assets/indexes/<id>.json
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AssetIndex {
+    #[serde(default, rename = "virtual")]
+    pub is_virtual: bool,
+    #[serde(default)]
+    pub map_to_resources: bool,
+    pub objects: HashMap<String, AssetObject>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AssetObject {
+    pub hash: String,
+    pub size: u64,
+}
+
+impl AssetObject {
+    /// Путь объекта относительно assets/objects и относительно resources URL
+    pub fn object_path(&self) -> Option<String> {
+        let prefix = self.hash.get(..2)?;
+        Some(format!("{}/{}", prefix, self.hash))
+    }
+}
+
+/// Путь артефакта в Maven-репозитории: group/artifact/version/artifact-version[-classifier].jar
+pub fn maven_artifact_path(name: &str) -> Option<String> {
+    let parts: Vec<&str> = name.split(':').collect();
+    if parts.len() < 3 {
+        return None;
+    }
+
+    let group = parts[0];
+    let artifact = parts[1];
+    let version_raw = parts[2];
+    let version = version_raw
+        .split_once('@')
+        .map(|(v, _ext)| v)
+        .unwrap_or(version_raw);
+    let classifier = if parts.len() >= 4 {
+        Some(parts[3])
+    } else {
+        None
+    };
+
+    let group_path = group.replace('.', "/");
+
+    let file_name = match classifier {
+        Some(c) => format!("{artifact}-{version}-{c}.jar"),
+        None => format!("{artifact}-{version}.jar"),
+    };
+
+    Some(format!("{group_path}/{artifact}/{version}/{file_name}"))
+}
+
+/// Парсит JSON с указанием файла, строки и поля в тексте ошибки
+pub fn parse_json<T: DeserializeOwned>(raw: &str, source: &str) -> anyhow::Result<T> {
+    serde_json::from_str(raw).map_err(|e| anyhow::anyhow!("Malformed {}: {}", source, e))
+}
+
+/// Читает и парсит JSON-файл
+pub fn read_json_file<T: DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
+    let raw = fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+    parse_json(&raw, &path.display().to_string())
+}
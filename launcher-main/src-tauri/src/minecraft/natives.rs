@@ -0,0 +1,78 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::classpath::{is_library_allowed, library_file};
+use super::model::VersionJson;
+use super::rules::Platform;
+
+/// Распаковывает нативы старого формата (библиотеки с `natives`) в `natives_dir`,
+/// пропуская пути из `extract.exclude`. Уже распакованные файлы того же размера не трогаются.
+pub fn extract_natives(
+    launch_dir: &Path,
+    version_json: &VersionJson,
+    natives_dir: &Path,
+    platform: &Platform,
+) -> anyhow::Result<()> {
+    let libraries_dir = launch_dir.join("libraries");
+
+    for lib in &version_json.libraries {
+        if !is_library_allowed(lib, platform) {
+            continue;
+        }
+
+        let path = match lib.native_artifact_path(platform) {
+            Some(path) => path,
+            None => continue,
+        };
+        let jar_path = library_file(&libraries_dir, &path);
+        if !jar_path.exists() {
+            return Err(anyhow::anyhow!(
+                "Native library {} is missing. Reinstall the game files.",
+                lib.name
+            ));
+        }
+
+        let exclude = lib
+            .extract
+            .as_ref()
+            .map(|e| e.exclude.as_slice())
+            .unwrap_or_default();
+
+        let file = fs::File::open(&jar_path)?;
+        let mut archive = zip::ZipArchive::new(file)
+            .map_err(|e| anyhow::anyhow!("Failed to open {}: {}", jar_path.display(), e))?;
+
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            if entry.is_dir()
+                || exclude
+                    .iter()
+                    .any(|prefix| entry.name().starts_with(prefix.as_str()))
+            {
+                continue;
+            }
+
+            let relative = match entry.enclosed_name() {
+                Some(path) => path,
+                None => continue,
+            };
+            let target = natives_dir.join(relative);
+
+            let up_to_date = fs::metadata(&target)
+                .map(|m| m.len() == entry.size())
+                .unwrap_or(false);
+            if up_to_date {
+                continue;
+            }
+
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut out = fs::File::create(&target)?;
+            io::copy(&mut entry, &mut out)?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,16 +1,41 @@
+use std::collections::BTreeMap;
 use std::fs;
-use std::io::Read;
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 
-use tauri::{AppHandle, Emitter, Runtime};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 
+use super::arguments::{game_arguments, jvm_arguments, ArgumentVars};
 use super::classpath::{build_classpath, offline_uuid_for_username};
+use super::rules::{Features, Platform};
 use super::types::{LaunchOptions, LogEvent, ProgressEvent};
-use super::version::{get_asset_index_id, get_main_class};
+use super::game_log::{GameLogLine, LogParser};
+use super::hooks::DEFAULT_HOOK_TIMEOUT_SECS;
+use super::jvm::{gc_arguments, split_args, DEFAULT_RAM_MB};
+use super::loading::LoadingTracker;
+use super::log_history::{self, SharedLog};
+use super::model::{JavaVersion, LoggingConfig, VersionJson};
+use super::registry::{GameLaunch, GameRegistry, InstanceReservation};
+use super::version::get_main_class;
 
-use serde_json::Value;
+/// Размер окна по умолчанию, если задана только одна сторона
+const DEFAULT_WINDOW_WIDTH: u32 = 854;
+const DEFAULT_WINDOW_HEIGHT: u32 = 480;
+
+const CLASSPATH_SEPARATOR: &str = if cfg!(target_os = "windows") { ";" } else { ":" };
+
+/// Файл с JVM аргументами в папке инстанса (передаётся как `@файл`)
+const JVM_ARGFILE: &str = "launcher-jvm.args";
+/// Длина командной строки, после которой Java 8 получает classpath через CLASSPATH
+/// (лимит Windows — 32767 символов на всю строку)
+const COMMAND_LINE_LIMIT: usize = 30000;
+/// Лимит Windows на значение переменной окружения, в том числе CLASSPATH
+const ENV_VAR_LIMIT: usize = 32767;
 
 /// Параметры для запуска Java процесса
 pub struct JavaLaunchParams {
@@ -19,81 +44,236 @@ pub struct JavaLaunchParams {
     pub version_jar_path: PathBuf,
     pub natives_dir: PathBuf,
     pub assets_dir: PathBuf,
+    /// Каталог для `${game_assets}` (виртуальные ассеты старых версий)
+    pub game_assets_dir: PathBuf,
     pub game_dir: PathBuf,
-    pub effective_version_json: Value,
+    pub effective_version_json: VersionJson,
+    /// Версия Java, которой будет запущена игра (None — определить не удалось)
+    pub java_major: Option<u32>,
+    /// Версия клиента в папке модов, для истории запусков
+    pub client_version: Option<String>,
+}
+
+/// Значения плейсхолдеров для аргументов из version.json
+fn launch_variables(
+    params: &JavaLaunchParams,
+    options: &LaunchOptions,
+    classpath: String,
+) -> ArgumentVars {
+    let version_type = params
+        .effective_version_json
+        .kind
+        .as_deref()
+        .unwrap_or("release");
+
+    let mut vars = ArgumentVars::new();
+    vars.insert("auth_player_name", options.username.clone());
+    vars.insert("auth_uuid", offline_uuid_for_username(&options.username));
+    vars.insert("auth_access_token", "0".into());
+    vars.insert("auth_session", "token:0".into());
+    vars.insert("auth_xuid", "0".into());
+    vars.insert("clientid", "0".into());
+    vars.insert("user_type", "mojang".into());
+    vars.insert("user_properties", "{}".into());
+    vars.insert("version_name", params.version_id.clone());
+    vars.insert("version_type", version_type.to_string());
+    vars.insert("game_directory", params.game_dir.to_string_lossy().to_string());
+    vars.insert("assets_root", params.assets_dir.to_string_lossy().to_string());
+    vars.insert("game_assets", params.game_assets_dir.to_string_lossy().to_string());
+    vars.insert(
+        "assets_index_name",
+        params.effective_version_json.asset_index_id().to_string(),
+    );
+    vars.insert("natives_directory", params.natives_dir.to_string_lossy().to_string());
+    vars.insert(
+        "library_directory",
+        params.launch_dir.join("libraries").to_string_lossy().to_string(),
+    );
+    vars.insert("classpath", classpath);
+    vars.insert("classpath_separator", CLASSPATH_SEPARATOR.to_string());
+    vars.insert("launcher_name", "boolean-launcher".into());
+    vars.insert("launcher_version", env!("CARGO_PKG_VERSION").into());
+    vars.insert(
+        "resolution_width",
+        options.window_width.unwrap_or(DEFAULT_WINDOW_WIDTH).to_string(),
+    );
+    vars.insert(
+        "resolution_height",
+        options.window_height.unwrap_or(DEFAULT_WINDOW_HEIGHT).to_string(),
+    );
+    if let Some(server) = &options.quick_play_server {
+        vars.insert("quickPlayMultiplayer", server.clone());
+    }
+    if let Some(world) = &options.quick_play_world {
+        vars.insert("quickPlaySingleplayer", world.clone());
+    }
+    vars
+}
+
+/// Фичи для условных аргументов version.json
+fn launch_features(options: &LaunchOptions) -> Features {
+    let mut features = Features::default();
+    if options.window_width.is_some() || options.window_height.is_some() {
+        features.enable("has_custom_resolution");
+    }
+    if options.fullscreen.unwrap_or(false) {
+        features.enable("is_fullscreen");
+    }
+    if options.quick_play_server.is_some() {
+        features.enable("is_quick_play_multiplayer");
+    }
+    if options.quick_play_world.is_some() {
+        features.enable("is_quick_play_singleplayer");
+    }
+    features
+}
+
+/// Собранная команда запуска. Argfile пишется только перед настоящим запуском,
+/// чтобы dry-run ничего не менял в папке инстанса.
+pub struct JavaCommand {
+    command: Command,
+    /// Путь @argfile и JVM аргументы для него (Java 9+)
+    argfile: Option<(PathBuf, Vec<String>)>,
+}
+
+impl JavaCommand {
+    /// Пишет argfile и запускает процесс
+    pub fn spawn(&mut self) -> anyhow::Result<Child> {
+        if let Some((path, args)) = &self.argfile {
+            write_argfile(path, args)?;
+        }
+        Ok(self.command.spawn()?)
+    }
+
+    /// Описание команды для dry-run: содержимое argfile подставлено вместо `@файл`
+    pub fn describe(&self) -> LaunchCommand {
+        let mut described = describe_command(&self.command);
+        if let Some((path, args)) = &self.argfile {
+            let reference = format!("@{}", path.to_string_lossy());
+            if let Some(index) = described.args.iter().position(|arg| *arg == reference) {
+                described.args.splice(index..=index, args.iter().cloned());
+                described.command_line =
+                    command_line(&described.env, &described.program, &described.args);
+            }
+        }
+        described
+    }
 }
 
 /// Создание и настройка Java команды
 pub fn build_java_command(
     params: &JavaLaunchParams,
     options: &LaunchOptions,
-) -> anyhow::Result<Command> {
+) -> anyhow::Result<JavaCommand> {
+    let platform = Platform::current();
     let classpath = build_classpath(
         &params.launch_dir,
         &params.effective_version_json,
         &params.version_jar_path,
+        platform,
     )?;
-    let classpath_separator = if cfg!(target_os = "windows") { ";" } else { ":" };
 
     // Используем java_path из options (уже проверенный/установленный путь)
     let java_cmd = options.java_path.clone().unwrap_or_else(|| "java".to_string());
-    let asset_index_id = get_asset_index_id(&params.effective_version_json);
-    let main_class = get_main_class(&params.effective_version_json);
+    let main_class = get_main_class(&params.effective_version_json)?;
 
-    let mut cmd = Command::new(&java_cmd);
+    let vars = launch_variables(params, options, classpath.join(CLASSPATH_SEPARATOR));
+    let features = launch_features(options);
+
+    // Обёртки (gamemoderun, mangohud, prime-run...) получают java как свой аргумент
+    let mut command_line = Vec::new();
+    for wrapper in options.wrapper_commands.iter().flatten() {
+        command_line.extend(split_args(wrapper)?);
+    }
+    command_line.push(java_cmd.clone());
+
+    let mut cmd = Command::new(&command_line[0]);
+    cmd.args(&command_line[1..]);
     cmd.current_dir(&params.launch_dir);
+    if let Some(env_vars) = &options.env_vars {
+        cmd.envs(env_vars);
+    }
+
+    let mut jvm_args = Vec::new();
 
     // JVM Memory allocation arguments
-    let ram_mb = options.ram_mb.unwrap_or(4096); // Default 4GB
-    cmd.arg(format!("-Xms{}M", ram_mb / 2)); // Initial heap = half of max
-    cmd.arg(format!("-Xmx{}M", ram_mb));     // Maximum heap
-
-    // Additional JVM optimization flags
-    cmd.arg("-XX:+UnlockExperimentalVMOptions");
-    cmd.arg("-XX:+UseG1GC");
-    cmd.arg("-XX:G1NewSizePercent=20");
-    cmd.arg("-XX:G1ReservePercent=20");
-    cmd.arg("-XX:MaxGCPauseMillis=50");
-    cmd.arg("-XX:G1HeapRegionSize=32M");
-
-    cmd.arg(format!(
-        "-Djava.library.path={}",
-        params.natives_dir.to_string_lossy()
-    ));
-    cmd.arg("-cp");
-    cmd.arg(classpath.join(classpath_separator));
-    cmd.arg(main_class);
+    let ram_mb = options.ram_mb.unwrap_or(DEFAULT_RAM_MB);
+    let min_ram_mb = options.min_ram_mb.unwrap_or((ram_mb / 2).max(1)); // Initial heap = half of max
+    // -Xmx0M и -Xms0M JVM не принимает
+    if ram_mb == 0 || min_ram_mb == 0 {
+        return Err(anyhow::anyhow!(
+            "Heap size must be at least 1 MB (ram_mb = {}, min_ram_mb = {})",
+            ram_mb,
+            min_ram_mb
+        ));
+    }
+    if min_ram_mb > ram_mb {
+        return Err(anyhow::anyhow!(
+            "Minimum heap ({} MB) is larger than maximum heap ({} MB)",
+            min_ram_mb,
+            ram_mb
+        ));
+    }
+    jvm_args.push(format!("-Xms{}M", min_ram_mb));
+    jvm_args.push(format!("-Xmx{}M", ram_mb));     // Maximum heap
+
+    // Сборщик мусора по выбранному пресету
+    jvm_args.extend(gc_arguments(
+        options.gc_preset.unwrap_or_default(),
+        params.java_major,
+        ram_mb,
+    )?);
+
+    jvm_args.extend(jvm_arguments(&params.effective_version_json, platform, &features, &vars));
+    let game_args = game_arguments(&params.effective_version_json, platform, &features, &vars);
 
-    cmd.arg("--username");
-    cmd.arg(&options.username);
-    cmd.arg("--uuid");
-    cmd.arg(offline_uuid_for_username(&options.username));
-    cmd.arg("--accessToken");
-    cmd.arg("0");
-    cmd.arg("--userType");
-    cmd.arg("mojang");
-    cmd.arg("--version");
-    cmd.arg(&params.version_id);
-    cmd.arg("--gameDir");
-    cmd.arg(params.game_dir.to_string_lossy().to_string());
-    cmd.arg("--assetsDir");
-    cmd.arg(params.assets_dir.to_string_lossy().to_string());
-    cmd.arg("--assetIndex");
-    cmd.arg(asset_index_id);
-
-    // Window size arguments
-    if let Some(width) = options.window_width {
-        cmd.arg("--width");
-        cmd.arg(width.to_string());
+    // Конфиг логирования Mojang (для новых версий — XML-события log4j)
+    if let Some(config) = active_log_config(params) {
+        let path = config.config_path(&params.assets_dir);
+        jvm_args.push(config.argument.replace("${path}", &path.to_string_lossy()));
     }
-    if let Some(height) = options.window_height {
-        cmd.arg("--height");
-        cmd.arg(height.to_string());
+
+    // Аргументы пользователя идут последними: JVM берёт последнее значение опции
+    if let Some(extra) = options.jvm_args.as_deref() {
+        jvm_args.extend(split_args(extra)?);
     }
-    if options.fullscreen.unwrap_or(false) {
-        cmd.arg("--fullscreen");
+
+    // Если `java -version` не ответил, верим javaVersion из version.json
+    let java_major = params.java_major.or_else(|| {
+        params
+            .effective_version_json
+            .java_version
+            .as_ref()
+            .map(|java| java.major_version)
+    });
+    let mut argfile = None;
+    if java_major.is_some_and(|major| major >= 9) {
+        // Java 9+: JVM опции и classpath уходят в @argfile, командная строка остаётся короткой
+        let path = params.game_dir.join(JVM_ARGFILE);
+        cmd.arg(format!("@{}", path.to_string_lossy()));
+        argfile = Some((path, jvm_args));
+    } else {
+        let length: usize = jvm_args.iter().chain(&game_args).map(|a| a.len() + 1).sum();
+        if length > COMMAND_LINE_LIMIT {
+            // Java 8 не знает argfile, но берёт classpath из переменной окружения
+            if let Some(classpath) = take_classpath_arg(&mut jvm_args) {
+                if cfg!(windows) && classpath.len() > ENV_VAR_LIMIT {
+                    return Err(anyhow::anyhow!(
+                        "Classpath is {} characters long, Windows allows at most {} in CLASSPATH. \
+                         Move the game folder to a shorter path or use a version that runs on Java 9+.",
+                        classpath.len(),
+                        ENV_VAR_LIMIT
+                    ));
+                }
+                cmd.env("CLASSPATH", classpath);
+            }
+        }
+        cmd.args(&jvm_args);
     }
 
+    cmd.arg(main_class);
+    cmd.args(game_args);
+
     // Скрываем консоль на Windows
     #[cfg(windows)]
     {
@@ -102,100 +282,217 @@ pub fn build_java_command(
         cmd.creation_flags(CREATE_NO_WINDOW);
     }
 
+    // Своя группа процессов: kill_game останавливает и java за командами-обёртками
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
 
-    Ok(cmd)
+    Ok(JavaCommand {
+        command: cmd,
+        argfile,
+    })
 }
 
-/// Запуск Java процесса и настройка мониторинга логов
-pub fn spawn_and_monitor<R: Runtime>(
-    mut child: Child,
-    params: &JavaLaunchParams,
-    app: &AppHandle<R>,
-) -> anyhow::Result<()> {
-    let logs_dir = params.launch_dir.join("run").join("logs");
-    let _ = fs::create_dir_all(&logs_dir);
-    let java_log_path = logs_dir.join("launcher-java.log");
+/// Итоговая команда запуска для dry-run
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Переменные, которые лаунчер задаёт поверх окружения лаунчера
+    pub env: BTreeMap<String, String>,
+    pub working_dir: Option<String>,
+    /// Команда одной строкой, как её набрать в терминале
+    pub command_line: String,
+}
 
-    // Записываем начальную информацию в лог
-    let _ = fs::write(
-        &java_log_path,
-        format!(
-            "version_id={}\nassets_dir={}\ngame_dir={}\n\n",
-            params.version_id,
-            params.assets_dir.to_string_lossy(),
-            params.game_dir.to_string_lossy()
-        ),
-    );
+/// Описание собранной команды без запуска
+fn describe_command(cmd: &Command) -> LaunchCommand {
+    let program = cmd.get_program().to_string_lossy().to_string();
+    let args: Vec<String> = cmd.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+    let env: BTreeMap<String, String> = cmd
+        .get_envs()
+        .filter_map(|(key, value)| {
+            Some((key.to_string_lossy().to_string(), value?.to_string_lossy().to_string()))
+        })
+        .collect();
 
-    // Мониторинг stdout
-    let app_for_stdout = app.clone();
-    let java_log_path_out = java_log_path.clone();
-    if let Some(mut out) = child.stdout.take() {
-        thread::spawn(move || {
-            let mut buf = Vec::new();
-            let _ = out.read_to_end(&mut buf);
-            if !buf.is_empty() {
-                let text = String::from_utf8_lossy(&buf).to_string();
-                let _ = fs::OpenOptions::new()
-                    .create(true)
-                    .append(true)
-                    .open(&java_log_path_out)
-                    .and_then(|mut f| std::io::Write::write_all(&mut f, text.as_bytes()));
-                let _ = app_for_stdout.emit(
-                    "minecraft-log",
-                    LogEvent {
-                        message: "[java stdout] (written to launcher-java.log)".into(),
-                    },
-                );
-            }
-        });
+    let command_line = command_line(&env, &program, &args);
+
+    LaunchCommand {
+        program,
+        args,
+        env,
+        working_dir: cmd.get_current_dir().map(|dir| dir.to_string_lossy().to_string()),
+        command_line,
     }
+}
 
-    // Мониторинг stderr
-    let app_for_stderr = app.clone();
-    let java_log_path_err = java_log_path.clone();
-    if let Some(mut err) = child.stderr.take() {
-        thread::spawn(move || {
-            let mut buf = Vec::new();
-            let _ = err.read_to_end(&mut buf);
-            if !buf.is_empty() {
-                let text = String::from_utf8_lossy(&buf).to_string();
-                let _ = fs::OpenOptions::new()
-                    .create(true)
-                    .append(true)
-                    .open(&java_log_path_err)
-                    .and_then(|mut f| std::io::Write::write_all(&mut f, text.as_bytes()));
-                let _ = app_for_stderr.emit(
-                    "minecraft-log",
-                    LogEvent {
-                        message: "[java stderr] (written to launcher-java.log)".into(),
-                    },
-                );
-            }
-        });
+/// Команда одной строкой: переменные окружения, программа и аргументы
+fn command_line(env: &BTreeMap<String, String>, program: &str, args: &[String]) -> String {
+    env.iter()
+        .map(|(key, value)| format!("{}={}", key, quote_arg(value)))
+        .chain(std::iter::once(quote_arg(program)))
+        .chain(args.iter().map(|arg| quote_arg(arg)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'') {
+        return arg.to_string();
+    }
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Пишет аргументы в формате Java argfile: по одному в строке, в кавычках
+fn write_argfile(path: &Path, args: &[String]) -> anyhow::Result<()> {
+    let content: String = args
+        .iter()
+        .map(|arg| format!("\"{}\"\n", arg.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect();
+    fs::write(path, content)
+        .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Убирает `-cp <classpath>` из аргументов и возвращает classpath
+fn take_classpath_arg(args: &mut Vec<String>) -> Option<String> {
+    let index = args
+        .iter()
+        .position(|arg| arg == "-cp" || arg == "-classpath" || arg == "--class-path")?;
+    if index + 1 >= args.len() {
+        return None;
     }
+    let classpath = args.remove(index + 1);
+    args.remove(index);
+    Some(classpath)
+}
+
+/// Конфиг логирования из version.json, если он скачан
+fn active_log_config(params: &JavaLaunchParams) -> Option<&LoggingConfig> {
+    params
+        .effective_version_json
+        .logging
+        .as_ref()
+        .and_then(|l| l.client.as_ref())
+        .filter(|config| config.config_path(&params.assets_dir).exists())
+}
 
-    // Ожидание завершения процесса
-    let app_for_wait = app.clone();
+/// Читает поток процесса в отдельном потоке: каждая строка пишется в лог и уходит событием
+fn stream_output<R: Runtime>(
+    source: impl Read + Send + 'static,
+    mut parser: LogParser,
+    log_file: Option<SharedLog>,
+    tracker: Arc<LoadingTracker<R>>,
+    app: AppHandle<R>,
+) {
     thread::spawn(move || {
-        match child.wait() {
-            Ok(status) => {
-                let _ = app_for_wait.emit(
-                    "minecraft-log",
-                    LogEvent {
-                        message: format!("Java exited: {} (see launcher-java.log)", status),
-                    },
-                );
-            }
-            Err(_e) => {
-                // Java wait error
+        let emit = |line: GameLogLine| {
+            tracker.observe(&line.message);
+            log_history::append_line(log_file.as_ref(), &line.to_log_line());
+            let _ = app.emit("minecraft-log", line);
+        };
+
+        let mut reader = BufReader::new(source);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let text = String::from_utf8_lossy(&buf);
+                    if let Some(line) = parser.push(text.trim_end_matches(['\r', '\n'])) {
+                        emit(line);
+                    }
+                }
             }
         }
+
+        if let Some(line) = parser.finish() {
+            emit(line);
+        }
+        tracker.stream_closed();
     });
+}
 
-    Ok(())
+/// Лог нового запуска (run/logs/launcher-java.log); лог прошлого запуска уходит в историю
+pub fn open_session_log(params: &JavaLaunchParams) -> Option<SharedLog> {
+    let logs_dir = params.launch_dir.join("run").join("logs");
+    log_history::start_session(
+        &logs_dir,
+        &format!(
+            "version_id={}\nassets_dir={}\ngame_dir={}\n\n",
+            params.version_id,
+            params.assets_dir.to_string_lossy(),
+            params.game_dir.to_string_lossy()
+        ),
+    )
+}
+
+/// Запуск Java процесса и настройка мониторинга логов
+pub fn spawn_and_monitor<R: Runtime>(
+    mut child: Child,
+    reservation: InstanceReservation,
+    params: &JavaLaunchParams,
+    options: &LaunchOptions,
+    log_file: Option<SharedLog>,
+    app: &AppHandle<R>,
+) -> u32 {
+    let xml = active_log_config(params).is_some_and(|config| config.is_xml());
+
+    // Вывод игры читаем построчно, чтобы консоль в лаунчере обновлялась сразу,
+    // а по вехам в логе понимаем, когда появится окно
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let streams = stdout.is_some() as u32 + stderr.is_some() as u32;
+    let tracker = LoadingTracker::start(app, streams);
+    if let Some(out) = stdout {
+        let parser = LogParser::new("stdout", xml);
+        stream_output(out, parser, log_file.clone(), tracker.clone(), app.clone());
+    }
+    if let Some(err) = stderr {
+        let parser = LogParser::new("stderr", xml);
+        stream_output(err, parser, log_file.clone(), tracker, app.clone());
+    }
+
+    // Процесс попадает в реестр: оттуда его видит фронтенд и там ждётся завершение
+    let registry = app.state::<GameRegistry>();
+    registry.track(
+        app,
+        reservation,
+        child,
+        GameLaunch {
+            launch_dir: params.launch_dir.clone(),
+            game_dir: params.game_dir.clone(),
+            version_id: params.version_id.clone(),
+            client_version: params.client_version.clone(),
+            java_path: options.java_path.clone().unwrap_or_else(|| "java".to_string()),
+            java_major: params.java_major,
+            ram_mb: options.ram_mb.unwrap_or(DEFAULT_RAM_MB),
+            required_java: params
+                .effective_version_json
+                .java_version
+                .clone()
+                .unwrap_or_else(JavaVersion::legacy)
+                .major_version,
+            post_exit_hook: options
+                .post_exit_hook
+                .clone()
+                .filter(|hook| !hook.trim().is_empty()),
+            hook_timeout: hook_timeout(options),
+            log: log_file,
+        },
+    )
+}
+
+/// Таймаут хуков из настроек
+pub fn hook_timeout(options: &LaunchOptions) -> Duration {
+    Duration::from_secs(options.hook_timeout_secs.unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS))
 }
 
 /// Отправка событий прогресса запуска
@@ -237,7 +534,6 @@ pub fn emit_launch_success<R: Runtime>(app: &AppHandle<R>) -> anyhow::Result<()>
             message: "The game window will appear shortly".into(),
         },
     )?;
-    app.emit("minecraft-loading", serde_json::json!({ "loading": true }))?;
     Ok(())
 }
 
@@ -283,3 +579,81 @@ pub fn emit_pre_launch<R: Runtime>(app: &AppHandle<R>, username: &str) -> anyhow
     )?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn params(dir: &Path, java_major: Option<u32>, version: serde_json::Value) -> JavaLaunchParams {
+        JavaLaunchParams {
+            launch_dir: dir.to_path_buf(),
+            version_id: "test".to_string(),
+            version_jar_path: dir.join("versions").join("test").join("test.jar"),
+            natives_dir: dir.join("natives"),
+            assets_dir: dir.join("assets"),
+            game_assets_dir: dir.join("assets"),
+            game_dir: dir.join("run"),
+            effective_version_json: serde_json::from_value(version).unwrap(),
+            java_major,
+            client_version: None,
+        }
+    }
+
+    fn options() -> LaunchOptions {
+        serde_json::from_value(json!({ "username": "Steve", "javaPath": "java" })).unwrap()
+    }
+
+    #[test]
+    fn argfile_is_written_only_on_spawn_and_inlined_in_dry_run() {
+        let dir = std::env::temp_dir().join(format!("launcher-test-{}", uuid::Uuid::new_v4()));
+        // `java -version` не ответил — решает javaVersion из version.json
+        let params = params(
+            &dir,
+            None,
+            json!({ "id": "test", "mainClass": "Main", "javaVersion": { "majorVersion": 21 } }),
+        );
+
+        let command = build_java_command(&params, &options()).unwrap();
+        let described = command.describe();
+
+        assert!(!params.game_dir.join(JVM_ARGFILE).exists());
+        assert!(!described.args.iter().any(|arg| arg.starts_with('@')));
+        assert!(described.args.contains(&"-Xmx4096M".to_string()));
+        let cp = described.args.iter().position(|arg| arg == "-cp").unwrap();
+        assert!(cp < described.args.iter().position(|arg| arg == "Main").unwrap());
+        assert!(described.command_line.contains("-Xmx4096M"));
+    }
+
+    #[test]
+    fn java_8_passes_jvm_options_on_the_command_line() {
+        let dir = std::env::temp_dir().join(format!("launcher-test-{}", uuid::Uuid::new_v4()));
+        let params = params(&dir, None, json!({ "id": "test", "mainClass": "Main" }));
+
+        let command = build_java_command(&params, &options()).unwrap();
+
+        assert!(command.argfile.is_none());
+        assert!(command.describe().args.contains(&"-cp".to_string()));
+    }
+
+    #[test]
+    fn zero_heap_sizes_are_rejected() {
+        let dir = std::env::temp_dir().join(format!("launcher-test-{}", uuid::Uuid::new_v4()));
+        let params = params(&dir, None, json!({ "id": "test", "mainClass": "Main" }));
+
+        for (ram_mb, min_ram_mb) in [(Some(0), None), (Some(0), Some(0)), (None, Some(0))] {
+            let mut options = options();
+            options.ram_mb = ram_mb;
+            options.min_ram_mb = min_ram_mb;
+            assert!(build_java_command(&params, &options).is_err());
+        }
+
+        let mut options = options();
+        options.ram_mb = Some(1);
+        let args = build_java_command(&params, &options)
+            .unwrap()
+            .describe()
+            .args;
+        assert!(args.contains(&"-Xms1M".to_string()));
+    }
+}
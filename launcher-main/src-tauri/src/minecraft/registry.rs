@@ -0,0 +1,487 @@
+//! Запущенные игры лаунчера. Реестр живёт в состоянии Tauri: по нему фронтенд видит и
+//! останавливает процессы, а лаунчер не даёт запустить один инстанс дважды.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use super::crash::{analyze_crash, CrashAnalysis, CrashContext};
+use super::history::{record_session, SessionRecord};
+use super::hooks::{run_hook, HookContext, HookStage};
+use super::log_history::SharedLog;
+use super::types::LogEvent;
+
+/// Как часто поток ожидания проверяет, завершился ли процесс
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Сколько последних завершений помнить для `get_game_status`
+const EXITED_HISTORY: usize = 20;
+
+/// Запущенная игра для фронтенда
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningGameInfo {
+    pub pid: u32,
+    /// Папка игры (`run`); два процесса в одной папке ломают миры и настройки
+    pub instance: String,
+    pub version_id: String,
+    pub java_path: String,
+    /// Время запуска, мс с начала эпохи
+    pub started_at: u64,
+    pub runtime_ms: u64,
+}
+
+/// Событие `minecraft-exited`
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GameExitedEvent {
+    pub pid: u32,
+    pub instance: String,
+    pub version_id: String,
+    /// None — процесс убит сигналом или код недоступен
+    pub exit_code: Option<i32>,
+    pub runtime_ms: u64,
+    /// Остановлен через `kill_game`
+    pub killed: bool,
+    /// Разбор падения, если игра завершилась с ошибкой.
+    /// В `get_game_status` появляется, когда разбор закончен.
+    pub crash: Option<CrashAnalysis>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "state", rename_all = "lowercase")]
+pub enum GameStatus {
+    Running(RunningGameInfo),
+    Exited(GameExitedEvent),
+}
+
+/// Что лаунчер запустил: нужно для списка игр и разбора падения
+pub struct GameLaunch {
+    pub launch_dir: PathBuf,
+    pub game_dir: PathBuf,
+    pub version_id: String,
+    /// Версия клиента в папке модов
+    pub client_version: Option<String>,
+    pub java_path: String,
+    /// Версия этой Java, определённая при запуске
+    pub java_major: Option<u32>,
+    pub ram_mb: u32,
+    /// Java из `javaVersion` версии
+    pub required_java: u32,
+    pub post_exit_hook: Option<String>,
+    pub hook_timeout: Duration,
+    /// Лог запуска, в который пишет и хук после выхода
+    pub log: Option<SharedLog>,
+}
+
+impl GameLaunch {
+    fn instance(&self) -> String {
+        self.game_dir.to_string_lossy().to_string()
+    }
+
+    fn hook_context(&self) -> HookContext {
+        HookContext {
+            launch_dir: self.launch_dir.clone(),
+            game_dir: self.game_dir.clone(),
+            version_id: self.version_id.clone(),
+            java_path: self.java_path.clone(),
+        }
+    }
+}
+
+struct RunningGame {
+    launch: GameLaunch,
+    started_at: SystemTime,
+    started: Instant,
+    killed: bool,
+    child: Arc<Mutex<Child>>,
+}
+
+impl RunningGame {
+    fn info(&self, pid: u32) -> RunningGameInfo {
+        RunningGameInfo {
+            pid,
+            instance: self.launch.instance(),
+            version_id: self.launch.version_id.clone(),
+            java_path: self.launch.java_path.clone(),
+            started_at: millis_since_epoch(self.started_at),
+            runtime_ms: self.started.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct GameRegistry {
+    games: Arc<Mutex<HashMap<u32, RunningGame>>>,
+    /// Папки, в которых идёт запуск: процесса ещё нет, но второй запуск туда уже нельзя.
+    /// Блокируется только после `games`.
+    reserved: Arc<Mutex<HashSet<String>>>,
+    exited: Arc<Mutex<VecDeque<GameExitedEvent>>>,
+}
+
+/// Папка игры, занятая запуском до появления процесса. Если запуск прервался,
+/// папка освобождается при drop; после `track` её держит запущенная игра.
+pub struct InstanceReservation {
+    registry: GameRegistry,
+    instance: String,
+}
+
+impl Drop for InstanceReservation {
+    fn drop(&mut self) {
+        self.registry
+            .reserved
+            .lock()
+            .unwrap()
+            .remove(&self.instance);
+    }
+}
+
+impl GameRegistry {
+    /// Все запущенные игры в порядке запуска
+    pub fn list(&self) -> Vec<RunningGameInfo> {
+        let games = self.games.lock().unwrap();
+        let mut list: Vec<RunningGameInfo> =
+            games.iter().map(|(pid, game)| game.info(*pid)).collect();
+        list.sort_by_key(|game| game.started_at);
+        list
+    }
+
+    /// Занимает папку игры на время запуска. Ошибка — в ней уже идёт игра или другой запуск.
+    pub fn reserve(&self, instance: &str) -> anyhow::Result<InstanceReservation> {
+        let games = self.games.lock().unwrap();
+        if let Some((pid, game)) = games
+            .iter()
+            .find(|(_, game)| game.launch.instance() == instance)
+        {
+            return Err(anyhow::anyhow!(
+                "Minecraft {} is already running in {} (pid {})",
+                game.launch.version_id,
+                instance,
+                pid
+            ));
+        }
+
+        if !self.reserved.lock().unwrap().insert(instance.to_string()) {
+            return Err(anyhow::anyhow!(
+                "Minecraft is already being launched in {}",
+                instance
+            ));
+        }
+        Ok(InstanceReservation {
+            registry: self.clone(),
+            instance: instance.to_string(),
+        })
+    }
+
+    /// Состояние процесса: запущен, недавно завершился или неизвестен (None)
+    pub fn status(&self, pid: u32) -> Option<GameStatus> {
+        if let Some(game) = self.games.lock().unwrap().get(&pid) {
+            return Some(GameStatus::Running(game.info(pid)));
+        }
+        self.exited
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|event| event.pid == pid)
+            .cloned()
+            .map(GameStatus::Exited)
+    }
+
+    /// Принудительно завершает игру вместе с дочерними процессами: с командами-обёртками
+    /// (gamemoderun, prime-run...) прямой потомок лаунчера — обёртка, а не java.
+    /// `minecraft-exited` отправит поток ожидания.
+    pub fn kill(&self, pid: u32) -> anyhow::Result<()> {
+        let child = {
+            let mut games = self.games.lock().unwrap();
+            let game = games
+                .get_mut(&pid)
+                .ok_or_else(|| anyhow::anyhow!("No running game with pid {}", pid))?;
+            game.killed = true;
+            game.child.clone()
+        };
+
+        let mut child = child.lock().unwrap();
+        if let Err(e) = kill_process_tree(pid) {
+            log::warn!("Failed to kill process tree of {}: {}", pid, e);
+        }
+        // Если дерево уже остановлено, kill просто ничего не сделает
+        child
+            .kill()
+            .map_err(|e| anyhow::anyhow!("Failed to kill game process {}: {}", pid, e))
+    }
+
+    /// Регистрирует запущенный процесс в занятой под него папке и ждёт его завершения
+    /// в отдельном потоке
+    pub fn track<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        reservation: InstanceReservation,
+        child: Child,
+        launch: GameLaunch,
+    ) -> u32 {
+        let pid = child.id();
+        debug_assert_eq!(reservation.instance, launch.instance());
+        let child = {
+            let mut games = self.games.lock().unwrap();
+            let child = Arc::new(Mutex::new(child));
+            games.insert(
+                pid,
+                RunningGame {
+                    launch,
+                    started_at: SystemTime::now(),
+                    started: Instant::now(),
+                    killed: false,
+                    child: child.clone(),
+                },
+            );
+            // Папку уже держит игра в `games`, бронь больше не нужна
+            drop(reservation);
+            child
+        };
+
+        let registry = self.clone();
+        let app = app.clone();
+        thread::spawn(move || {
+            let status = wait_for_exit(&child);
+            registry.finish(&app, pid, status);
+        });
+
+        pid
+    }
+
+    fn finish<R: Runtime>(&self, app: &AppHandle<R>, pid: u32, exit_code: Option<i32>) {
+        // Игра переходит в `exited` сразу и под блокировкой `games`, чтобы `status`
+        // находил её, пока идут разбор падения и хук после выхода
+        let (game, mut event) = {
+            let mut games = self.games.lock().unwrap();
+            let game = match games.remove(&pid) {
+                Some(game) => game,
+                None => return,
+            };
+            let event = GameExitedEvent {
+                pid,
+                instance: game.launch.instance(),
+                version_id: game.launch.version_id.clone(),
+                exit_code,
+                runtime_ms: game.started.elapsed().as_millis() as u64,
+                killed: game.killed,
+                crash: None,
+            };
+
+            let mut exited = self.exited.lock().unwrap();
+            if exited.len() == EXITED_HISTORY {
+                exited.pop_front();
+            }
+            exited.push_back(event.clone());
+            (game, event)
+        };
+
+        // Остановку через kill_game падением не считаем
+        event.crash = if exit_code != Some(0) && !game.killed {
+            Some(analyze_crash(&CrashContext {
+                launch_dir: &game.launch.launch_dir,
+                game_dir: &game.launch.game_dir,
+                java_major: game.launch.java_major,
+                ram_mb: game.launch.ram_mb,
+                required_java: game.launch.required_java,
+                started_at: game.started_at,
+            }))
+        } else {
+            None
+        };
+        if event.crash.is_some() {
+            let mut exited = self.exited.lock().unwrap();
+            if let Some(stored) = exited.iter_mut().rev().find(|stored| stored.pid == pid) {
+                stored.crash = event.crash.clone();
+            }
+        }
+
+        let _ = app.emit(
+            "minecraft-log",
+            LogEvent {
+                message: match exit_code {
+                    Some(code) => format!("Java exited with code {} (see launcher-java.log)", code),
+                    None => "Java exited without exit code (see launcher-java.log)".to_string(),
+                },
+            },
+        );
+        let _ = app.emit("minecraft-exited", &event);
+        if let Some(crash) = &event.crash {
+            let _ = app.emit(
+                "minecraft-log",
+                LogEvent {
+                    message: format!("Crash: {} — {}", crash.message, crash.fix),
+                },
+            );
+            let _ = app.emit("minecraft-crash", crash);
+        }
+
+        let session = SessionRecord {
+            started_at: millis_since_epoch(game.started_at),
+            ended_at: millis_since_epoch(SystemTime::now()),
+            duration_ms: event.runtime_ms,
+            version_id: event.version_id.clone(),
+            client_version: game.launch.client_version.clone(),
+            java_path: game.launch.java_path.clone(),
+            exit_code,
+            killed: event.killed,
+            crashed: event.crash.is_some(),
+            crash_report: event
+                .crash
+                .as_ref()
+                .is_some_and(|crash| crash.crash_report.is_some()),
+            crash_cause: event.crash.as_ref().map(|crash| crash.cause),
+        };
+        if let Err(e) = record_session(&game.launch.game_dir, session) {
+            log::warn!("Failed to save launch history: {}", e);
+        }
+
+        if let Some(hook) = game.launch.post_exit_hook.as_deref() {
+            let extra_env = [
+                ("MC_PID", pid.to_string()),
+                (
+                    "MC_EXIT_CODE",
+                    exit_code.map(|code| code.to_string()).unwrap_or_default(),
+                ),
+                ("MC_RUNTIME_MS", event.runtime_ms.to_string()),
+                ("MC_KILLED", event.killed.to_string()),
+                ("MC_CRASHED", event.crash.is_some().to_string()),
+            ];
+            if let Err(e) = run_hook(
+                app,
+                HookStage::PostExit,
+                hook,
+                &game.launch.hook_context(),
+                &extra_env,
+                game.launch.hook_timeout,
+                game.launch.log.as_ref(),
+            ) {
+                log::warn!("{}", e);
+            }
+        }
+    }
+}
+
+/// Убивает процесс и всех его потомков. На unix игра и хуки запускаются в своей группе
+/// процессов (pgid = pid), на Windows дерево обходит taskkill.
+pub(super) fn kill_process_tree(pid: u32) -> std::io::Result<()> {
+    #[cfg(windows)]
+    let status = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?
+    };
+
+    #[cfg(not(windows))]
+    let status = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", pid)])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!("exited with {}", status)))
+    }
+}
+
+/// Ждёт завершения, не держа блокировку: иначе `kill` не смог бы добраться до процесса
+fn wait_for_exit(child: &Mutex<Child>) -> Option<i32> {
+    loop {
+        match child.lock().unwrap().try_wait() {
+            Ok(Some(status)) => return status.code(),
+            Ok(None) => {}
+            Err(_) => return None,
+        }
+        thread::sleep(EXIT_POLL_INTERVAL);
+    }
+}
+
+fn millis_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn launch(game_dir: PathBuf) -> GameLaunch {
+        GameLaunch {
+            launch_dir: game_dir.clone(),
+            game_dir,
+            version_id: "1.21.4".to_string(),
+            client_version: None,
+            java_path: "java".to_string(),
+            java_major: Some(21),
+            ram_mb: 4096,
+            required_java: 21,
+            post_exit_hook: None,
+            hook_timeout: Duration::from_secs(1),
+            log: None,
+        }
+    }
+
+    #[test]
+    fn reservation_blocks_second_launch_until_dropped() {
+        let registry = GameRegistry::default();
+
+        let reservation = registry.reserve("/games/run").unwrap();
+        let error = registry.reserve("/games/run").err().unwrap();
+        assert!(error.to_string().contains("already being launched"));
+        // Другие папки не заняты
+        drop(registry.reserve("/games/other").unwrap());
+
+        drop(reservation);
+        assert!(registry.reserve("/games/run").is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn tracked_game_holds_instance_until_exit() {
+        use std::os::unix::process::CommandExt;
+
+        let dir = std::env::temp_dir().join(format!("launcher-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let instance = dir.to_string_lossy().to_string();
+        let registry = GameRegistry::default();
+        let app = tauri::test::mock_app();
+
+        let reservation = registry.reserve(&instance).unwrap();
+        let child = Command::new("sleep")
+            .arg("30")
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let pid = registry.track(app.handle(), reservation, child, launch(dir.clone()));
+
+        assert!(registry.reserved.lock().unwrap().is_empty());
+        let error = registry.reserve(&instance).err().unwrap();
+        assert!(error.to_string().contains(&format!("pid {}", pid)));
+        assert_eq!(registry.list().len(), 1);
+
+        registry.kill(pid).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !matches!(registry.status(pid), Some(GameStatus::Exited(_))) {
+            assert!(Instant::now() < deadline, "game did not exit");
+            thread::sleep(EXIT_POLL_INTERVAL);
+        }
+        assert!(registry.reserve(&instance).is_ok());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
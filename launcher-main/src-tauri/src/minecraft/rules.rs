@@ -0,0 +1,345 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+
+use regex::Regex;
+
+use super::model::{OsRule, Rule, RuleAction};
+
+/// Включённые фичи лаунчера для правил `features` в version.json
+/// (`has_custom_resolution`, `is_quick_play_multiplayer` и т.д.).
+/// Неизвестные фичи считаются выключенными, как в официальном лаунчере.
+#[derive(Default, Clone, Debug)]
+pub struct Features(HashSet<String>);
+
+impl Features {
+    pub fn enable(&mut self, name: &str) {
+        self.0.insert(name.to_string());
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.0.contains(name)
+    }
+}
+
+/// Платформа, для которой вычисляются правила, в терминах version.json:
+/// `os_name` — windows/osx/linux, `arch` — x86/x86_64/arm64,
+/// `os_version` — то, что Java отдаёт в `os.version` (например, `10.0` или `6.5.0-41-generic`).
+#[derive(Clone, Debug)]
+pub struct Platform {
+    pub os_name: String,
+    pub arch: String,
+    pub os_version: String,
+}
+
+impl Platform {
+    pub fn new(os_name: &str, arch: &str, os_version: &str) -> Self {
+        Self {
+            os_name: os_name.to_string(),
+            arch: arch.to_string(),
+            os_version: os_version.to_string(),
+        }
+    }
+
+    /// Платформа, на которой запущен лаунчер
+    pub fn current() -> &'static Platform {
+        static CURRENT: OnceLock<Platform> = OnceLock::new();
+        CURRENT.get_or_init(|| Platform::new(current_os_name(), current_arch(), &current_os_version()))
+    }
+
+    /// Значение `${arch}` в классификаторах нативов
+    pub fn bits(&self) -> &'static str {
+        if self.arch == "x86" {
+            "32"
+        } else {
+            "64"
+        }
+    }
+}
+
+fn current_os_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "osx"
+    } else {
+        "linux"
+    }
+}
+
+fn current_arch() -> &'static str {
+    if cfg!(target_arch = "x86") {
+        "x86"
+    } else if cfg!(target_arch = "aarch64") {
+        "arm64"
+    } else {
+        "x86_64"
+    }
+}
+
+/// Версия ОС в том виде, в котором её видит Java
+fn current_os_version() -> String {
+    #[cfg(target_os = "windows")]
+    {
+        // RTL_OSVERSIONINFOW из ntdll. GetVersionEx без манифеста врёт про Windows 10+,
+        // а `cmd /C ver` из GUI-приложения мигает окном консоли.
+        #[repr(C)]
+        struct OsVersionInfo {
+            size: u32,
+            major: u32,
+            minor: u32,
+            build: u32,
+            platform_id: u32,
+            csd_version: [u16; 128],
+        }
+
+        #[link(name = "ntdll")]
+        extern "system" {
+            fn RtlGetVersion(info: *mut OsVersionInfo) -> i32;
+        }
+
+        let mut info = OsVersionInfo {
+            size: std::mem::size_of::<OsVersionInfo>() as u32,
+            major: 0,
+            minor: 0,
+            build: 0,
+            platform_id: 0,
+            csd_version: [0; 128],
+        };
+        // SAFETY: структура той раскладки, что ждёт ntdll; size заполнен
+        if unsafe { RtlGetVersion(&mut info) } != 0 {
+            return String::new();
+        }
+        // Java отдаёт в os.version только major.minor: "10.0" и для Windows 11
+        format!("{}.{}", info.major, info.minor)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("sw_vers")
+            .arg("-productVersion")
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            .unwrap_or_default()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        std::fs::read_to_string("/proc/sys/kernel/osrelease")
+            .map(|v| v.trim().to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Вычисляет список `rules`. Без правил элемент разрешён; с правилами — запрещён,
+/// пока последнее подходящее правило не скажет `allow`.
+pub fn rules_allow(rules: Option<&[Rule]>, platform: &Platform, features: &Features) -> bool {
+    let rules = match rules {
+        Some(r) => r,
+        None => return true,
+    };
+
+    let mut allowed = false;
+    for rule in rules {
+        if os_matches(rule.os.as_ref(), platform) && features_match(rule.features.as_ref(), features) {
+            allowed = rule.action == RuleAction::Allow;
+        }
+    }
+
+    allowed
+}
+
+fn os_matches(os: Option<&OsRule>, platform: &Platform) -> bool {
+    let os = match os {
+        Some(os) => os,
+        None => return true,
+    };
+
+    let name_matches = os.name.as_deref().map_or(true, |name| name == platform.os_name);
+    let arch_matches = os.arch.as_deref().map_or(true, |arch| arch == platform.arch);
+    let version_matches = os
+        .version
+        .as_deref()
+        .map_or(true, |pattern| version_matches(pattern, &platform.os_version));
+
+    name_matches && arch_matches && version_matches
+}
+
+/// Шаблоны `os.version` компилируются один раз: правила вычисляются для каждой библиотеки
+/// и каждого аргумента, а шаблонов в version.json всего несколько.
+/// Некорректный шаблон не совпадает ни с чем.
+fn version_matches(pattern: &str, os_version: &str) -> bool {
+    static CACHE: OnceLock<Mutex<HashMap<String, Option<Regex>>>> = OnceLock::new();
+    let mut cache = CACHE.get_or_init(Default::default).lock().unwrap();
+    cache
+        .entry(pattern.to_string())
+        .or_insert_with(|| Regex::new(pattern).ok())
+        .as_ref()
+        .is_some_and(|re| re.is_match(os_version))
+}
+
+fn features_match(required: Option<&HashMap<String, bool>>, features: &Features) -> bool {
+    let required = match required {
+        Some(r) => r,
+        None => return true,
+    };
+
+    required
+        .iter()
+        .all(|(name, value)| *value == features.is_enabled(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minecraft::model::Library;
+
+    const OSES: [&str; 3] = ["windows", "linux", "osx"];
+    const ARCHES: [&str; 3] = ["x86", "x86_64", "arm64"];
+
+    fn rules(json: &str) -> Vec<Rule> {
+        serde_json::from_str(json).expect("valid rules")
+    }
+
+    fn every_platform() -> impl Iterator<Item = Platform> {
+        OSES.iter()
+            .flat_map(|os| ARCHES.iter().map(move |arch| Platform::new(os, arch, "")))
+    }
+
+    #[test]
+    fn no_rules_allow_everything() {
+        for platform in every_platform() {
+            assert!(rules_allow(None, &platform, &Features::default()));
+        }
+    }
+
+    #[test]
+    fn os_name_rule_matches_only_that_os() {
+        let rules = rules(r#"[{"action": "allow", "os": {"name": "linux"}}]"#);
+        for platform in every_platform() {
+            assert_eq!(
+                rules_allow(Some(&rules), &platform, &Features::default()),
+                platform.os_name == "linux",
+                "{:?}",
+                platform
+            );
+        }
+    }
+
+    #[test]
+    fn last_matching_rule_wins() {
+        // LWJGL 2: везде, кроме macOS
+        let rules =
+            rules(r#"[{"action": "allow"}, {"action": "disallow", "os": {"name": "osx"}}]"#);
+        for platform in every_platform() {
+            assert_eq!(
+                rules_allow(Some(&rules), &platform, &Features::default()),
+                platform.os_name != "osx",
+                "{:?}",
+                platform
+            );
+        }
+    }
+
+    #[test]
+    fn arch_rule_matches_only_that_arch() {
+        let rules = rules(r#"[{"action": "allow", "os": {"name": "windows", "arch": "arm64"}}]"#);
+        for platform in every_platform() {
+            assert_eq!(
+                rules_allow(Some(&rules), &platform, &Features::default()),
+                platform.os_name == "windows" && platform.arch == "arm64",
+                "{:?}",
+                platform
+            );
+        }
+    }
+
+    #[test]
+    fn x86_rule_adds_jvm_flag_only_on_32_bit() {
+        let rules = rules(r#"[{"action": "allow", "os": {"arch": "x86"}}]"#);
+        for platform in every_platform() {
+            assert_eq!(
+                rules_allow(Some(&rules), &platform, &Features::default()),
+                platform.arch == "x86",
+                "{:?}",
+                platform
+            );
+        }
+    }
+
+    #[test]
+    fn os_version_is_a_regex() {
+        let rules =
+            rules(r#"[{"action": "allow", "os": {"name": "windows", "version": "^10\\."}}]"#);
+        let features = Features::default();
+        assert!(rules_allow(
+            Some(&rules),
+            &Platform::new("windows", "x86_64", "10.0"),
+            &features
+        ));
+        assert!(!rules_allow(
+            Some(&rules),
+            &Platform::new("windows", "x86_64", "6.1"),
+            &features
+        ));
+        assert!(!rules_allow(
+            Some(&rules),
+            &Platform::new("linux", "x86_64", "10.0"),
+            &features
+        ));
+    }
+
+    #[test]
+    fn invalid_os_version_pattern_never_matches() {
+        let rules = rules(r#"[{"action": "allow", "os": {"version": "["}}]"#);
+        for platform in every_platform() {
+            assert!(!rules_allow(Some(&rules), &platform, &Features::default()));
+        }
+    }
+
+    #[test]
+    fn features_must_all_match() {
+        let rules = rules(
+            r#"[{"action": "allow", "features": {"has_custom_resolution": true, "is_demo_user": false}}]"#,
+        );
+        let platform = Platform::new("linux", "x86_64", "");
+
+        let mut features = Features::default();
+        assert!(!rules_allow(Some(&rules), &platform, &features));
+
+        features.enable("has_custom_resolution");
+        assert!(rules_allow(Some(&rules), &platform, &features));
+
+        features.enable("is_demo_user");
+        assert!(!rules_allow(Some(&rules), &platform, &features));
+    }
+
+    #[test]
+    fn native_classifier_follows_platform() {
+        let lib: Library = serde_json::from_str(
+            r#"{
+                "name": "org.lwjgl.lwjgl:lwjgl-platform:2.9.4",
+                "natives": {
+                    "windows": "natives-windows-${arch}",
+                    "linux": "natives-linux",
+                    "osx": "natives-osx"
+                }
+            }"#,
+        )
+        .expect("valid library");
+
+        for platform in every_platform() {
+            let expected = match (platform.os_name.as_str(), platform.arch.as_str()) {
+                ("windows", "x86") => "natives-windows-32",
+                ("windows", _) => "natives-windows-64",
+                ("linux", _) => "natives-linux",
+                _ => "natives-osx",
+            };
+            assert_eq!(
+                lib.native_classifier(&platform).as_deref(),
+                Some(expected),
+                "{:?}",
+                platform
+            );
+        }
+    }
+}
@@ -1,5 +1,9 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+use super::jvm::GcPreset;
+
 #[derive(Serialize)]
 pub(crate) struct WipeResult {
     pub(crate) success: bool,
@@ -26,6 +30,64 @@ pub struct LaunchOptions {
     /// Custom install path from user settings
     #[serde(alias = "installPath")]
     pub install_path: Option<String>,
+    /// Server address to join right after start (quick play)
+    #[serde(alias = "quickPlayServer")]
+    pub quick_play_server: Option<String>,
+    /// Singleplayer world to open right after start (quick play)
+    #[serde(alias = "quickPlayWorld")]
+    pub quick_play_world: Option<String>,
+    /// Installed version to launch (folder name in launch/versions)
+    #[serde(alias = "versionId")]
+    pub version_id: Option<String>,
+    /// Minimum heap in megabytes (-Xms); half of ram_mb if not set
+    #[serde(alias = "minRamMb")]
+    pub min_ram_mb: Option<u32>,
+    /// Garbage collector preset
+    #[serde(alias = "gcPreset")]
+    pub gc_preset: Option<GcPreset>,
+    /// Extra JVM arguments, shell-style quoting (e.g. `-Dfoo="a b"`)
+    #[serde(alias = "jvmArgs")]
+    pub jvm_args: Option<String>,
+    /// Commands that wrap java, outermost first (e.g. `gamemoderun`, `mangohud --dlsym`)
+    #[serde(alias = "wrapperCommands")]
+    pub wrapper_commands: Option<Vec<String>>,
+    /// Extra environment variables for the game process
+    #[serde(alias = "envVars")]
+    pub env_vars: Option<HashMap<String, String>>,
+    /// Build the command and report it without starting the game
+    #[serde(alias = "dryRun")]
+    pub dry_run: Option<bool>,
+    /// Shell command to run before the game starts; non-zero exit aborts the launch
+    #[serde(alias = "preLaunchHook")]
+    pub pre_launch_hook: Option<String>,
+    /// Shell command to run after the game exits
+    #[serde(alias = "postExitHook")]
+    pub post_exit_hook: Option<String>,
+    /// Hook timeout in seconds (60 if not set)
+    #[serde(alias = "hookTimeoutSecs")]
+    pub hook_timeout_secs: Option<u64>,
+}
+
+/// Version found in launch/versions
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledVersion {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub inherits_from: Option<String>,
+    /// Mod loader of the profile (fabric, quilt, forge, neoforge), none for vanilla
+    pub loader: Option<String>,
+    /// version.json exists and parses
+    pub json_complete: bool,
+    /// Game jar is present (own or inherited)
+    pub jar_complete: bool,
+}
+
+impl InstalledVersion {
+    pub fn is_complete(&self) -> bool {
+        self.json_complete && self.jar_complete
+    }
 }
 
 #[derive(Serialize, Clone)]
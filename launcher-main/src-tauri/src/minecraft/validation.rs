@@ -0,0 +1,456 @@
+//! Проверка установки перед запуском: всё, без чего Java гарантированно упадёт,
+//! находится заранее и возвращается списком проблем с подсказками.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::classpath::{is_library_allowed, library_file};
+use super::jvm::system_memory;
+use super::model::{read_json_file, AssetIndex, JavaVersion, VersionJson};
+use super::rules::Platform;
+use super::version::{detect_loader, load_effective_version, resolve_version_jar, LibraryOverride};
+use crate::client_installer::java_major_version;
+
+/// Сколько имён показывать в сообщении, если проблемных файлов много
+const LISTED_NAMES: usize = 5;
+/// Сколько памяти оставить системе помимо кучи игры
+const SYSTEM_RESERVE_MB: u64 = 2048;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Игра не запустится
+    Error,
+    /// Игра запустится, но, скорее всего, не так, как ожидается
+    Warning,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    /// Что проверялось: version_json, game_jar, libraries, natives, asset_index, assets, java, mods
+    pub check: String,
+    pub severity: Severity,
+    pub message: String,
+    /// Что сделать пользователю
+    pub fix: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    pub version_id: String,
+    pub issues: Vec<ValidationIssue>,
+    /// Библиотеки родителей, заменённые библиотеками потомков при мерже inheritsFrom
+    pub library_overrides: Vec<LibraryOverride>,
+}
+
+impl ValidationReport {
+    pub fn has_errors(&self) -> bool {
+        self.issues.iter().any(|i| i.severity == Severity::Error)
+    }
+
+    fn push(&mut self, check: &str, severity: Severity, message: String, fix: &str) {
+        self.issues.push(ValidationIssue {
+            check: check.to_string(),
+            severity,
+            message,
+            fix: fix.to_string(),
+        });
+    }
+
+    fn error(&mut self, check: &str, message: String, fix: &str) {
+        self.push(check, Severity::Error, message, fix);
+    }
+
+    fn warning(&mut self, check: &str, message: String, fix: &str) {
+        self.push(check, Severity::Warning, message, fix);
+    }
+
+    /// Текст для лога и ошибки запуска
+    pub fn summary(&self) -> String {
+        self.issues
+            .iter()
+            .map(|i| format!("[{:?}] {} — {}", i.severity, i.message, i.fix))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Java, которой будет запущена игра
+#[derive(Debug, Clone)]
+pub struct JavaInfo {
+    pub path: String,
+    /// Старший номер версии; None — Java не запускается
+    pub major: Option<u32>,
+}
+
+impl JavaInfo {
+    /// Определяет версию через `java -version` в блокирующем пуле tokio
+    pub async fn probe(path: String) -> Self {
+        let probe_path = path.clone();
+        let major = tokio::task::spawn_blocking(move || java_major_version(Path::new(&probe_path)))
+            .await
+            .ok()
+            .flatten();
+        Self { path, major }
+    }
+}
+
+/// Проверяет установленную версию без обращения к сети.
+/// `java` — Java, которой будет запущена игра (None — Java не найдена).
+pub async fn validate_installation(
+    launch_dir: &Path,
+    version_id: &str,
+    java: Option<JavaInfo>,
+) -> ValidationReport {
+    let mut report = ValidationReport {
+        version_id: version_id.to_string(),
+        issues: Vec::new(),
+        library_overrides: Vec::new(),
+    };
+
+    let version_json = match load_effective_version(launch_dir, version_id, None).await {
+        Ok(effective) => {
+            report.library_overrides = effective.library_overrides;
+            effective.json
+        }
+        Err(e) => {
+            report.error(
+                "version_json",
+                format!("Version JSON for {} cannot be loaded: {}", version_id, e),
+                "Reinstall the version or wipe client data.",
+            );
+            return report;
+        }
+    };
+
+    // Файлы проверяются синхронно (каждый ассет, каждый мод, `java -version`),
+    // поэтому не на потоках async-рантайма
+    let launch_dir = launch_dir.to_path_buf();
+    let fallback = report.clone();
+    tokio::task::spawn_blocking(move || {
+        check_files(&launch_dir, &version_json, java, &mut report);
+        report
+    })
+    .await
+    .unwrap_or_else(|e| {
+        let mut report = fallback;
+        report.error(
+            "version_json",
+            format!("Installation check failed: {}", e),
+            "Try again or restart the launcher.",
+        );
+        report
+    })
+}
+
+fn check_files(
+    launch_dir: &Path,
+    version_json: &VersionJson,
+    java: Option<JavaInfo>,
+    report: &mut ValidationReport,
+) {
+    let version_id = report.version_id.clone();
+    if version_json.main_class.is_none() {
+        report.error(
+            "version_json",
+            format!("Version {} has no mainClass", version_id),
+            "Reinstall the version or wipe client data.",
+        );
+    }
+
+    if resolve_version_jar(launch_dir, &version_id).is_none() {
+        report.error(
+            "game_jar",
+            format!("Game jar for {} is missing", version_id),
+            "Reinstall the version.",
+        );
+    }
+
+    check_libraries(launch_dir, version_json, Platform::current(), report);
+    check_assets(launch_dir, version_json, report);
+    check_java(version_json, java, report);
+    check_mods(&launch_dir.join("run").join("mods"), version_json, report);
+}
+
+fn check_libraries(
+    launch_dir: &Path,
+    version_json: &VersionJson,
+    platform: &Platform,
+    report: &mut ValidationReport,
+) {
+    let libraries_dir = launch_dir.join("libraries");
+    let mut missing = Vec::new();
+    let mut corrupt = Vec::new();
+    let mut missing_natives = Vec::new();
+    let mut has_natives = false;
+
+    for lib in &version_json.libraries {
+        if !is_library_allowed(lib, platform) {
+            continue;
+        }
+
+        if lib.name.contains(":natives-") {
+            has_natives = true;
+        }
+
+        if !lib.is_natives_only() {
+            if let Some(path) = lib.artifact_path() {
+                let expected_size = lib
+                    .downloads
+                    .as_ref()
+                    .and_then(|d| d.artifact.as_ref())
+                    .and_then(|a| a.size)
+                    .or(lib.size);
+                match fs::metadata(library_file(&libraries_dir, &path)) {
+                    Err(_) => missing.push(lib.name.clone()),
+                    Ok(m) if expected_size.is_some_and(|size| size != m.len()) => {
+                        corrupt.push(lib.name.clone())
+                    }
+                    Ok(_) => {}
+                }
+            }
+        }
+
+        if let Some(path) = lib.native_artifact_path(platform) {
+            has_natives = true;
+            if !library_file(&libraries_dir, &path).exists() {
+                missing_natives.push(lib.name.clone());
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        report.error(
+            "libraries",
+            format!(
+                "{} libraries are missing: {}",
+                missing.len(),
+                list_names(&missing)
+            ),
+            "Launch again with internet access to download them, or reinstall the version.",
+        );
+    }
+    if !corrupt.is_empty() {
+        report.error(
+            "libraries",
+            format!(
+                "{} libraries have the wrong size: {}",
+                corrupt.len(),
+                list_names(&corrupt)
+            ),
+            "Delete the libraries folder and launch again to re-download them.",
+        );
+    }
+    if !missing_natives.is_empty() {
+        report.error(
+            "natives",
+            format!(
+                "Native libraries are missing: {}",
+                list_names(&missing_natives)
+            ),
+            "Launch again with internet access to download them, or reinstall the version.",
+        );
+    }
+
+    let uses_lwjgl = version_json
+        .libraries
+        .iter()
+        .any(|lib| lib.name.starts_with("org.lwjgl"));
+    if uses_lwjgl && !has_natives {
+        report.error(
+            "natives",
+            format!(
+                "Version has no LWJGL natives for {} {}",
+                platform.os_name, platform.arch
+            ),
+            "This game version does not support your platform; pick a newer version.",
+        );
+    }
+}
+
+fn check_assets(launch_dir: &Path, version_json: &VersionJson, report: &mut ValidationReport) {
+    let assets_dir = launch_dir.join("assets");
+    let index_id = version_json.asset_index_id();
+    let index_path = assets_dir.join("indexes").join(format!("{index_id}.json"));
+
+    let index: AssetIndex = match read_json_file(&index_path) {
+        Ok(index) => index,
+        Err(e) => {
+            report.error(
+                "asset_index",
+                format!("Asset index {} is missing or broken: {}", index_id, e),
+                "Reinstall the version to download assets.",
+            );
+            return;
+        }
+    };
+
+    let objects_dir = assets_dir.join("objects");
+    let mut missing = Vec::new();
+    for (name, object) in &index.objects {
+        let intact = object
+            .object_path()
+            .and_then(|path| fs::metadata(objects_dir.join(path)).ok())
+            .is_some_and(|m| m.len() == object.size);
+        if !intact {
+            missing.push(name.clone());
+        }
+    }
+
+    if !missing.is_empty() {
+        missing.sort();
+        report.error(
+            "assets",
+            format!(
+                "{} of {} asset objects are missing or damaged: {}",
+                missing.len(),
+                index.objects.len(),
+                list_names(&missing)
+            ),
+            "Reinstall the version to re-download assets.",
+        );
+    }
+}
+
+fn check_java(version_json: &VersionJson, java: Option<JavaInfo>, report: &mut ValidationReport) {
+    let required = version_json
+        .java_version
+        .clone()
+        .unwrap_or_else(JavaVersion::legacy)
+        .major_version;
+
+    let java = match java {
+        Some(java) => java,
+        None => {
+            report.error(
+                "java",
+                format!("Java {} is not installed", required),
+                "Install Java from the launcher settings or set a Java path.",
+            );
+            return;
+        }
+    };
+
+    let java_path = java.path.as_str();
+    let actual = match java.major {
+        Some(major) => major,
+        None => {
+            report.error(
+                "java",
+                format!("Java at {} cannot be started", java_path),
+                "Check the Java path in settings or reinstall Java.",
+            );
+            return;
+        }
+    };
+
+    if actual < required {
+        report.error(
+            "java",
+            format!(
+                "Version needs Java {}, but {} is Java {}",
+                required, java_path, actual
+            ),
+            "Clear the custom Java path so the launcher picks the right Java.",
+        );
+    } else if required <= 8 && actual > 8 {
+        report.warning(
+            "java",
+            format!(
+                "Old versions are built for Java 8, but {} is Java {}",
+                java_path, actual
+            ),
+            "If the game crashes on start, use Java 8.",
+        );
+    }
+}
+
+fn check_mods(mods_dir: &Path, version_json: &VersionJson, report: &mut ValidationReport) {
+    let entries = match fs::read_dir(mods_dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    let mut jars = Vec::new();
+    let mut broken = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("jar") {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        let readable = fs::File::open(&path)
+            .ok()
+            .and_then(|file| zip::ZipArchive::new(file).ok())
+            .is_some();
+        if !readable {
+            broken.push(name.clone());
+        }
+        jars.push(name);
+    }
+
+    if !broken.is_empty() {
+        report.error(
+            "mods",
+            format!("Mods are not valid jar files: {}", list_names(&broken)),
+            "Delete these files from the mods folder and reinstall the mods.",
+        );
+    }
+
+    if !jars.is_empty() && detect_loader(version_json).is_none() {
+        report.warning(
+            "mods",
+            format!(
+                "{} mods are installed, but this version has no mod loader",
+                jars.len()
+            ),
+            "Launch a Fabric version to use mods.",
+        );
+    }
+}
+
+/// Сравнивает `ram_mb` с памятью системы. Слишком большой кучу не запрещаем —
+/// только предупреждаем, чтобы пользователь знал, откуда лаги и вылеты.
+pub fn check_memory(ram_mb: u32, report: &mut ValidationReport) {
+    let memory = match system_memory() {
+        Some(memory) => memory,
+        None => return,
+    };
+
+    if u64::from(ram_mb) + SYSTEM_RESERVE_MB > memory.total_mb {
+        report.warning(
+            "memory",
+            format!(
+                "{} MB is allocated to the game, but the system has only {} MB in total",
+                ram_mb, memory.total_mb
+            ),
+            "Lower the memory setting so the system keeps at least 2 GB.",
+        );
+    } else if u64::from(ram_mb) > memory.available_mb {
+        report.warning(
+            "memory",
+            format!(
+                "{} MB is allocated to the game, but only {} MB is free right now",
+                ram_mb, memory.available_mb
+            ),
+            "Close other programs or lower the memory setting.",
+        );
+    }
+}
+
+fn list_names(names: &[String]) -> String {
+    let mut listed = names
+        .iter()
+        .take(LISTED_NAMES)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if names.len() > LISTED_NAMES {
+        listed.push_str(&format!(" and {} more", names.len() - LISTED_NAMES));
+    }
+    listed
+}
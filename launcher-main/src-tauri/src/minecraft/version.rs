@@ -1,150 +1,547 @@
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use serde_json::Value;
+use serde::Serialize;
 
-const VERSION_MANIFEST_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+use super::manifest::ManifestClient;
+use super::model::VersionJson;
+use super::types::InstalledVersion;
 
-/// Поиск установленной версии в папке versions
-pub fn find_installed_version_id(launch_dir: &PathBuf) -> Option<String> {
+/// Библиотека родителя, которую при мерже заменила библиотека потомка
+/// с тем же `group:artifact[:classifier]`
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryOverride {
+    /// Версия (ID) родителя и выброшенная библиотека
+    pub parent_version: String,
+    pub parent_library: String,
+    /// Версия (ID) потомка и оставшаяся библиотека
+    pub child_version: String,
+    pub child_library: String,
+}
+
+/// version.json после мержа цепочки inheritsFrom
+pub struct EffectiveVersion {
+    pub json: VersionJson,
+    /// Что выброшено при разрешении конфликтов библиотек, от корня к потомку
+    pub library_overrides: Vec<LibraryOverride>,
+}
+
+/// Все версии в папке versions, отсортированные по ID
+pub fn list_installed_versions(launch_dir: &Path) -> Vec<InstalledVersion> {
     let versions_dir = launch_dir.join("versions");
-    let entries = fs::read_dir(&versions_dir).ok()?;
+    let entries = match fs::read_dir(&versions_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
 
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if !path.is_dir() {
-            continue;
-        }
+    let mut versions: Vec<InstalledVersion> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| {
+            let version_id = entry.file_name().to_string_lossy().to_string();
+            let json_path = entry.path().join(format!("{version_id}.json"));
+            let json = VersionJson::load(&json_path).ok();
+
+            InstalledVersion {
+                kind: json.as_ref().and_then(|j| j.kind.clone()),
+                inherits_from: json.as_ref().and_then(|j| j.inherits_from.clone()),
+                loader: json.as_ref().and_then(detect_loader),
+                json_complete: json.is_some(),
+                jar_complete: resolve_version_jar(launch_dir, &version_id).is_some(),
+                id: version_id,
+            }
+        })
+        .collect();
+
+    versions.sort_by(|a, b| a.id.cmp(&b.id));
+    versions
+}
+
+/// Первая по ID полностью установленная версия
+pub fn find_installed_version_id(launch_dir: &Path) -> Option<String> {
+    list_installed_versions(launch_dir)
+        .into_iter()
+        .find(InstalledVersion::is_complete)
+        .map(|v| v.id)
+}
+
+/// Загрузчик модов по библиотекам профиля
+pub(crate) fn detect_loader(json: &VersionJson) -> Option<String> {
+    const LOADERS: [(&str, &str); 4] = [
+        ("net.fabricmc:fabric-loader:", "fabric"),
+        ("org.quiltmc:quilt-loader:", "quilt"),
+        ("net.neoforged:", "neoforge"),
+        ("net.minecraftforge:forge:", "forge"),
+    ];
+
+    json.libraries.iter().find_map(|lib| {
+        LOADERS
+            .iter()
+            .find(|(prefix, _)| lib.name.starts_with(prefix))
+            .map(|(_, loader)| loader.to_string())
+    })
+}
+
+/// Путь к jar игры для версии. У профилей загрузчиков (Fabric) своего jar нет —
+/// он берётся из поля `jar` или у родителя по цепочке inheritsFrom.
+pub fn resolve_version_jar(launch_dir: &Path, version_id: &str) -> Option<PathBuf> {
+    let versions_dir = launch_dir.join("versions");
+    let mut current = version_id.to_string();
+    let mut visited = HashSet::new();
 
-        let version_id = entry.file_name().to_string_lossy().to_string();
-        let json_path = path.join(format!("{version_id}.json"));
-        let jar_path = path.join(format!("{version_id}.jar"));
-        if json_path.exists() && jar_path.exists() {
-            return Some(version_id);
+    while visited.insert(current.clone()) {
+        let dir = versions_dir.join(&current);
+        let jar_path = dir.join(format!("{current}.jar"));
+        if jar_path.exists() {
+            return Some(jar_path);
         }
+
+        let json = VersionJson::load(&dir.join(format!("{current}.json"))).ok()?;
+        current = json.jar.or(json.inherits_from)?;
     }
 
     None
 }
 
-/// Загрузка и мерж version.json с родительским (inheritsFrom)
-pub fn load_effective_version_json(
-    launch_dir: &PathBuf,
+/// Загрузка version.json и мерж со всей цепочкой родителей (inheritsFrom).
+/// Отсутствующие vanilla родители скачиваются через `manifest`, без него — считаются ошибкой.
+pub async fn load_effective_version_json(
+    launch_dir: &Path,
     version_id: &str,
-) -> anyhow::Result<Value> {
-    let version_dir = launch_dir.join("versions").join(version_id);
-    let version_json_path = version_dir.join(format!("{version_id}.json"));
-
-    let version_json_raw = fs::read_to_string(&version_json_path)?;
-    let version_json: Value = serde_json::from_str(&version_json_raw)?;
-
-    let mut effective_version_json = version_json.clone();
-
-    if let Some(parent_id) = version_json.get("inheritsFrom").and_then(|v| v.as_str()) {
-        let parent_dir = launch_dir.join("versions").join(parent_id);
-        let parent_json_path = parent_dir.join(format!("{parent_id}.json"));
-        
-        // Если родительский JSON не существует, пробуем скачать
-        if !parent_json_path.exists() {
-            if let Err(_e) = download_vanilla_version_json_sync(parent_id, &parent_dir) {
-                // Failed to download parent JSON
-            }
+    manifest: Option<&ManifestClient>,
+) -> anyhow::Result<VersionJson> {
+    Ok(load_effective_version(launch_dir, version_id, manifest)
+        .await?
+        .json)
+}
+
+/// То же, что `load_effective_version_json`, плюс список библиотек, заменённых при мерже
+pub async fn load_effective_version(
+    launch_dir: &Path,
+    version_id: &str,
+    manifest: Option<&ManifestClient>,
+) -> anyhow::Result<EffectiveVersion> {
+    let chain = load_version_chain(launch_dir, version_id, manifest).await?;
+
+    // Мержим от корня цепочки (vanilla) к запрошенной версии
+    let mut levels = chain.into_iter().rev();
+    let mut effective_version_json = levels
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Version {} not found", version_id))?;
+    let mut library_overrides = Vec::new();
+    for child in levels {
+        let (merged, overrides) = merge_version_json(effective_version_json, child);
+        effective_version_json = merged;
+        library_overrides.extend(overrides);
+    }
+
+    effective_version_json.inherits_from = None;
+    effective_version_json.id = version_id.to_string();
+
+    Ok(EffectiveVersion {
+        json: effective_version_json,
+        library_overrides,
+    })
+}
+
+/// Читает version.json и всех его предков. Первый элемент — сама версия, последний — корень.
+async fn load_version_chain(
+    launch_dir: &Path,
+    version_id: &str,
+    manifest: Option<&ManifestClient>,
+) -> anyhow::Result<Vec<VersionJson>> {
+    let versions_dir = launch_dir.join("versions");
+    let mut chain: Vec<VersionJson> = Vec::new();
+    let mut visited: Vec<String> = Vec::new();
+    let mut current = version_id.to_string();
+
+    loop {
+        if visited.contains(&current) {
+            visited.push(current);
+            return Err(anyhow::anyhow!(
+                "Cycle in inheritsFrom chain: {}",
+                visited.join(" -> ")
+            ));
         }
-        
-        if parent_json_path.exists() {
-            let parent_raw = fs::read_to_string(&parent_json_path)?;
-            let parent_json: Value = serde_json::from_str(&parent_raw)?;
-
-            // Мерж библиотек
-            let mut merged_libs: Vec<Value> = Vec::new();
-            if let Some(parent_libs) = parent_json.get("libraries").and_then(|v| v.as_array()) {
-                merged_libs.extend(parent_libs.iter().cloned());
-            }
-            if let Some(child_libs) = version_json.get("libraries").and_then(|v| v.as_array()) {
-                merged_libs.extend(child_libs.iter().cloned());
-            }
-            if !merged_libs.is_empty() {
-                effective_version_json["libraries"] = Value::Array(merged_libs);
-            } else if let Some(parent_libs) = parent_json.get("libraries") {
-                effective_version_json["libraries"] = parent_libs.clone();
-            }
 
-            // Наследование assetIndex
-            if effective_version_json.get("assetIndex").is_none() {
-                if let Some(parent_ai) = parent_json.get("assetIndex") {
-                    effective_version_json["assetIndex"] = parent_ai.clone();
-                }
-            }
+        let version_dir = versions_dir.join(&current);
+        let version_json_path = version_dir.join(format!("{current}.json"));
 
-            // Наследование mainClass
-            if effective_version_json.get("mainClass").is_none() {
-                if let Some(parent_mc) = parent_json.get("mainClass") {
-                    effective_version_json["mainClass"] = parent_mc.clone();
-                }
+        // Если JSON родителя не существует, пробуем скачать
+        if !version_json_path.exists() && !chain.is_empty() {
+            let child = visited.last().map(String::as_str).unwrap_or(version_id);
+            let manifest = manifest.ok_or_else(|| {
+                anyhow::anyhow!("Parent version {} of {} is not installed", current, child)
+            })?;
+            if let Err(e) = manifest.ensure_version_json(&current).await {
+                return Err(anyhow::anyhow!(
+                    "Parent version {} of {} is not installed and could not be downloaded: {}",
+                    current,
+                    child,
+                    e
+                ));
             }
-            
-            // Merge complete
-        } else {
-            // Parent JSON not found, libraries from vanilla will be missing
         }
-    }
 
-    Ok(effective_version_json)
+        let json = VersionJson::load(&version_json_path)?;
+        let parent = json.inherits_from.clone();
+
+        visited.push(current);
+        chain.push(json);
+
+        match parent {
+            Some(parent_id) => current = parent_id,
+            None => return Ok(chain),
+        }
+    }
 }
 
-/// Извлечение asset index ID из version.json
-pub fn get_asset_index_id(version_json: &Value) -> String {
-    version_json
-        .get("assetIndex")
-        .and_then(|v| v.get("id"))
-        .and_then(|v| v.as_str())
-        .unwrap_or("19")
-        .to_string()
+/// Мерж дочернего version.json поверх родительского по правилам официального лаунчера:
+/// - `libraries`: сначала библиотеки потомка, затем родителя; библиотека родителя с тем же
+///   `group:artifact[:classifier]`, что и у потомка, выбрасывается;
+/// - `arguments.game` / `arguments.jvm`: аргументы родителя, затем потомка;
+/// - остальные поля (`mainClass`, `type`, `javaVersion`, `logging`, `downloads`,
+///   `assetIndex`, `minecraftArguments` и т.д.): значение потомка заменяет родительское.
+///
+/// Вторым значением возвращаются выброшенные библиотеки родителя.
+fn merge_version_json(
+    parent: VersionJson,
+    child: VersionJson,
+) -> (VersionJson, Vec<LibraryOverride>) {
+    let child_keys: HashSet<String> = child
+        .libraries
+        .iter()
+        .map(|lib| lib.conflict_key())
+        .collect();
+    let mut overrides = Vec::new();
+    let mut libraries = child.libraries;
+    for lib in parent.libraries {
+        let key = lib.conflict_key();
+        if child_keys.contains(&key) {
+            let replacement = libraries
+                .iter()
+                .find(|l| l.conflict_key() == key)
+                .map(|l| l.name.clone())
+                .unwrap_or_default();
+            overrides.push(LibraryOverride {
+                parent_version: parent.id.clone(),
+                parent_library: lib.name,
+                child_version: child.id.clone(),
+                child_library: replacement,
+            });
+            continue;
+        }
+        libraries.push(lib);
+    }
+
+    let arguments = match (parent.arguments, child.arguments) {
+        (Some(mut parent_args), Some(child_args)) => {
+            parent_args.game.extend(child_args.game);
+            parent_args.jvm.extend(child_args.jvm);
+            Some(parent_args)
+        }
+        (parent_args, child_args) => child_args.or(parent_args),
+    };
+
+    let mut extra = parent.extra;
+    extra.extend(child.extra);
+
+    let merged = VersionJson {
+        id: child.id,
+        inherits_from: child.inherits_from,
+        kind: child.kind.or(parent.kind),
+        main_class: child.main_class.or(parent.main_class),
+        minecraft_arguments: child.minecraft_arguments.or(parent.minecraft_arguments),
+        arguments,
+        asset_index: child.asset_index.or(parent.asset_index),
+        assets: child.assets.or(parent.assets),
+        libraries,
+        downloads: child.downloads.or(parent.downloads),
+        java_version: child.java_version.or(parent.java_version),
+        logging: child.logging.or(parent.logging),
+        jar: child.jar.or(parent.jar),
+        release_time: child.release_time.or(parent.release_time),
+        time: child.time.or(parent.time),
+        extra,
+    };
+
+    (merged, overrides)
 }
 
 /// Извлечение main class из version.json
-pub fn get_main_class(version_json: &Value) -> String {
+pub fn get_main_class(version_json: &VersionJson) -> anyhow::Result<String> {
     version_json
-        .get("mainClass")
-        .and_then(|v| v.as_str())
-        .unwrap_or("net.fabricmc.loader.impl.launch.knot.KnotClient")
-        .to_string()
+        .main_class
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("Version {} has no mainClass", version_json.id))
 }
 
-/// Синхронное скачивание vanilla version JSON
-fn download_vanilla_version_json_sync(version_id: &str, target_dir: &PathBuf) -> anyhow::Result<()> {
-    use std::io::Write;
-    
-    // Скачиваем манифест версий
-    let manifest_response = reqwest::blocking::get(VERSION_MANIFEST_URL)?;
-    let manifest: Value = manifest_response.json()?;
-    
-    // Ищем нужную версию
-    let versions = manifest
-        .get("versions")
-        .and_then(|v| v.as_array())
-        .ok_or_else(|| anyhow::anyhow!("No versions array in manifest"))?;
-    
-    let version_entry = versions
-        .iter()
-        .find(|v| v.get("id").and_then(|id| id.as_str()) == Some(version_id))
-        .ok_or_else(|| anyhow::anyhow!("Version {} not found in manifest", version_id))?;
-    
-    let version_url = version_entry
-        .get("url")
-        .and_then(|u| u.as_str())
-        .ok_or_else(|| anyhow::anyhow!("No URL for version {}", version_id))?;
-    
-    // Скачиваем JSON версии
-    let version_response = reqwest::blocking::get(version_url)?;
-    let version_json_content = version_response.text()?;
-    
-    // Создаём директорию и сохраняем файл
-    fs::create_dir_all(target_dir)?;
-    let json_path = target_dir.join(format!("{}.json", version_id));
-    
-    let mut file = fs::File::create(&json_path)?;
-    file.write_all(version_json_content.as_bytes())?;
-    
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn version(value: serde_json::Value) -> VersionJson {
+        serde_json::from_value(value).expect("valid version JSON")
+    }
+
+    /// Раскладывает version.json по versions/<id>/<id>.json во временной папке
+    fn launch_dir_with(versions: &[serde_json::Value]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("launcher-test-{}", uuid::Uuid::new_v4()));
+        for json in versions {
+            let id = json["id"].as_str().unwrap();
+            let version_dir = dir.join("versions").join(id);
+            fs::create_dir_all(&version_dir).unwrap();
+            fs::write(version_dir.join(format!("{id}.json")), json.to_string()).unwrap();
+        }
+        dir
+    }
+
+    fn game_args(json: &VersionJson) -> Vec<String> {
+        json.arguments
+            .as_ref()
+            .unwrap()
+            .game
+            .iter()
+            .map(|arg| match arg {
+                crate::minecraft::model::Argument::Plain(value) => value.clone(),
+                other => panic!("unexpected argument {:?}", other),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn merges_three_level_chain_from_root_to_child() {
+        let dir = launch_dir_with(&[
+            json!({
+                "id": "1.21.4",
+                "type": "release",
+                "mainClass": "net.minecraft.client.main.Main",
+                "arguments": { "game": ["--username", "${auth_player_name}"], "jvm": ["-Xss1M"] },
+                "javaVersion": { "component": "java-runtime-delta", "majorVersion": 21 },
+                "assetIndex": { "id": "19", "url": "https://example.com/19.json" },
+                "libraries": [{ "name": "org.ow2.asm:asm:9.6" }]
+            }),
+            json!({
+                "id": "fabric-loader-0.16.10-1.21.4",
+                "inheritsFrom": "1.21.4",
+                "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+                "arguments": { "game": ["--fabric"], "jvm": ["-DFabricMcEmu=net.minecraft.client.main.Main"] },
+                "libraries": [{ "name": "net.fabricmc:fabric-loader:0.16.10" }]
+            }),
+            json!({
+                "id": "modpack",
+                "inheritsFrom": "fabric-loader-0.16.10-1.21.4",
+                "arguments": { "game": ["--modpack"] },
+                "libraries": [{ "name": "com.example:modpack:1.0" }]
+            }),
+        ]);
+
+        let merged = load_effective_version_json(&dir, "modpack", None)
+            .await
+            .unwrap();
+
+        assert_eq!(merged.id, "modpack");
+        assert!(merged.inherits_from.is_none());
+        assert_eq!(
+            merged.main_class.as_deref(),
+            Some("net.fabricmc.loader.impl.launch.knot.KnotClient")
+        );
+        assert_eq!(merged.kind.as_deref(), Some("release"));
+        assert_eq!(merged.java_version.as_ref().unwrap().major_version, 21);
+        assert_eq!(merged.asset_index_id(), "19");
+        // Аргументы: корень, затем каждый потомок по порядку
+        assert_eq!(
+            game_args(&merged),
+            ["--username", "${auth_player_name}", "--fabric", "--modpack"]
+        );
+        assert_eq!(merged.arguments.as_ref().unwrap().jvm.len(), 2);
+        // Библиотеки: потомок впереди родителя
+        let names: Vec<&str> = merged.libraries.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "com.example:modpack:1.0",
+                "net.fabricmc:fabric-loader:0.16.10",
+                "org.ow2.asm:asm:9.6"
+            ]
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn rejects_inherits_from_cycle() {
+        let dir = launch_dir_with(&[
+            json!({ "id": "a", "inheritsFrom": "b" }),
+            json!({ "id": "b", "inheritsFrom": "c" }),
+            json!({ "id": "c", "inheritsFrom": "a" }),
+        ]);
+
+        let error = load_effective_version_json(&dir, "a", None)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("a -> b -> c -> a"), "{}", error);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn missing_parent_without_manifest_is_an_error() {
+        let dir = launch_dir_with(&[json!({ "id": "child", "inheritsFrom": "1.21.4" })]);
+
+        let error = load_effective_version_json(&dir, "child", None)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("Parent version 1.21.4 of child"),
+            "{}",
+            error
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn child_fields_replace_parent_fields() {
+        let parent = version(json!({
+            "id": "parent",
+            "type": "release",
+            "mainClass": "parent.Main",
+            "minecraftArguments": "--parent",
+            "javaVersion": { "majorVersion": 8 },
+            "logging": { "client": {
+                "argument": "-Dlog4j.configurationFile=${path}",
+                "type": "log4j2-xml",
+                "file": { "id": "client-1.12.xml", "url": "https://example.com/parent.xml" }
+            } },
+            "downloads": { "client": { "url": "https://example.com/parent.jar" } },
+            "assets": "legacy"
+        }));
+        let child = version(json!({
+            "id": "child",
+            "type": "snapshot",
+            "mainClass": "child.Main",
+            "minecraftArguments": "--child",
+            "javaVersion": { "majorVersion": 17 },
+            "logging": { "client": {
+                "argument": "-Dlog4j.configurationFile=${path}",
+                "type": "log4j2-xml",
+                "file": { "id": "child.xml", "url": "https://example.com/child.xml" }
+            } },
+            "downloads": { "client": { "url": "https://example.com/child.jar" } },
+            "assets": "1.12"
+        }));
+
+        let (merged, _) = merge_version_json(parent, child);
+
+        assert_eq!(merged.kind.as_deref(), Some("snapshot"));
+        assert_eq!(merged.main_class.as_deref(), Some("child.Main"));
+        assert_eq!(merged.minecraft_arguments.as_deref(), Some("--child"));
+        assert_eq!(merged.java_version.unwrap().major_version, 17);
+        assert_eq!(merged.logging.unwrap().client.unwrap().file.id, "child.xml");
+        assert_eq!(
+            merged.downloads.unwrap()["client"].url,
+            "https://example.com/child.jar"
+        );
+        assert_eq!(merged.assets.as_deref(), Some("1.12"));
+    }
+
+    #[test]
+    fn missing_child_fields_fall_back_to_parent() {
+        let parent = version(json!({
+            "id": "parent",
+            "type": "release",
+            "minecraftArguments": "--parent",
+            "javaVersion": { "majorVersion": 8 },
+            "logging": { "client": {
+                "argument": "-Dlog4j.configurationFile=${path}",
+                "type": "log4j2-xml",
+                "file": { "id": "client-1.7.xml", "url": "https://example.com/parent.xml" }
+            } },
+            "downloads": { "client": { "url": "https://example.com/parent.jar" } },
+            "customField": "parent"
+        }));
+        let child = version(json!({ "id": "child", "inheritsFrom": "parent" }));
+
+        let (merged, _) = merge_version_json(parent, child);
+
+        assert_eq!(merged.id, "child");
+        assert_eq!(merged.kind.as_deref(), Some("release"));
+        assert_eq!(merged.minecraft_arguments.as_deref(), Some("--parent"));
+        assert_eq!(merged.java_version.unwrap().major_version, 8);
+        assert_eq!(
+            merged.logging.unwrap().client.unwrap().file.id,
+            "client-1.7.xml"
+        );
+        assert!(merged.downloads.unwrap().contains_key("client"));
+        assert_eq!(merged.extra["customField"], "parent");
+    }
+
+    #[test]
+    fn child_library_replaces_parent_library_with_same_key() {
+        let parent = version(json!({
+            "id": "1.21.4",
+            "libraries": [
+                { "name": "org.ow2.asm:asm:9.6" },
+                { "name": "org.lwjgl:lwjgl:3.3.3" },
+                { "name": "org.lwjgl:lwjgl:3.3.3:natives-linux" }
+            ]
+        }));
+        let child = version(json!({
+            "id": "fabric-loader-0.16.10-1.21.4",
+            "libraries": [
+                { "name": "org.ow2.asm:asm:9.7.1" },
+                { "name": "net.fabricmc:fabric-loader:0.16.10" }
+            ]
+        }));
+
+        let (merged, overrides) = merge_version_json(parent, child);
+
+        let names: Vec<&str> = merged.libraries.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "org.ow2.asm:asm:9.7.1",
+                "net.fabricmc:fabric-loader:0.16.10",
+                "org.lwjgl:lwjgl:3.3.3",
+                "org.lwjgl:lwjgl:3.3.3:natives-linux"
+            ]
+        );
+        assert_eq!(
+            overrides,
+            [LibraryOverride {
+                parent_version: "1.21.4".to_string(),
+                parent_library: "org.ow2.asm:asm:9.6".to_string(),
+                child_version: "fabric-loader-0.16.10-1.21.4".to_string(),
+                child_library: "org.ow2.asm:asm:9.7.1".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn arguments_concatenate_parent_first() {
+        let parent = version(json!({
+            "id": "parent",
+            "arguments": { "game": ["--parent"], "jvm": ["-Dparent"] }
+        }));
+        let child = version(json!({
+            "id": "child",
+            "arguments": { "game": ["--child"], "jvm": ["-Dchild"] }
+        }));
+        let (merged, _) = merge_version_json(parent, child);
+        assert_eq!(game_args(&merged), ["--parent", "--child"]);
+
+        // Аргументы только у одной стороны берутся как есть
+        let parent = version(json!({ "id": "parent", "arguments": { "game": ["--parent"] } }));
+        let child = version(json!({ "id": "child" }));
+        assert_eq!(
+            game_args(&merge_version_json(parent, child).0),
+            ["--parent"]
+        );
+    }
 }